@@ -76,8 +76,8 @@ pub fn frame(x: f32, y: f32) -> Result<(), JsValue> {
     let quad = shapes::quad(top_left, top_right, btm_left, btm_right);
     wizdraw.clear();
 
-    wizdraw.fill_cbc(&quad, &Texture::Debug, SsaaConfig::None);
-    wizdraw.fill_cbc(&quad, &texture, SsaaConfig::None);
+    wizdraw.fill_cbc(&quad, &Texture::Debug, FillRule::EvenOdd, SsaaConfig::None);
+    wizdraw.fill_cbc(&quad, &texture, FillRule::EvenOdd, SsaaConfig::None);
 
     let data = Clamped(wizdraw.pixels().as_slice());
     let image_data = ImageData::new_with_u8_clamped_array_and_sh(data, w, h)?;
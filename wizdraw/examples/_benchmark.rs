@@ -1,5 +1,5 @@
 use rgb::AsPixels;
-use wizdraw::{CubicBezier, Canvas, Color, Texture, Point, contour, SsaaConfig};
+use wizdraw::{CubicBezier, Canvas, Color, Texture, Point, contour, StrokeStyle, SsaaConfig, FillRule};
 
 use std::time::Instant;
 
@@ -33,7 +33,7 @@ pub fn benchmark<C: Canvas>(canvas: &mut C, wf: f32, hf: f32) {
     let (tex_w, tex_h, tex_p) = read_grid_png();
 
     let mut line = Vec::new();
-    contour(path.as_slice(), 5.0, &mut line, 0.5);
+    contour(path.as_slice(), &StrokeStyle::new(5.0), &mut line, 0.5);
 
     let green = Color::new(100, 200, 150, 255);
     let contour = Texture::SolidColor(green);
@@ -56,9 +56,9 @@ pub fn benchmark<C: Canvas>(canvas: &mut C, wf: f32, hf: f32) {
             let then = Instant::now();
             let num = 20;
             for _ in 0..num {
-                canvas.fill_cbc(&path, &Texture::Debug, ssaa);
-                canvas.fill_cbc(&path, &texture, ssaa);
-                canvas.fill_cbc(&line, &contour, ssaa);
+                canvas.fill_cbc(&path, &Texture::Debug, FillRule::EvenOdd, ssaa);
+                canvas.fill_cbc(&path, &texture, FillRule::EvenOdd, ssaa);
+                canvas.fill_cbc(&line, &contour, FillRule::NonZero, ssaa);
             }
             let avg_ms = then.elapsed().as_micros() / num;
             let fps = 1000000 / avg_ms;
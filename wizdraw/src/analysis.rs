@@ -0,0 +1,133 @@
+//! Area, centroid and orientation of paths
+//!
+//! Paths which don't end where they start are treated as if they were closed
+//! by a straight line. Results are exact, up to floating point rounding.
+
+use super::*;
+
+// Gauss-Legendre quadrature on [0, 1], exact for polynomials up to degree 9
+const GAUSS_LEGENDRE: [(f32, f32); 5] = [
+    (0.04691008, 0.11846344),
+    (0.23076534, 0.23931434),
+    (0.5, 0.28444445),
+    (0.76923466, 0.23931434),
+    (0.9530899, 0.11846344),
+];
+
+/// Direction in which a closed path goes around its interior
+///
+/// Directions are given as they appear on screen, with the Y axis pointing down.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+}
+
+/// Area enclosed by a path, positive for clockwise paths
+///
+/// Parts of the path which wind around the same area twice count twice;
+/// parts which wind in opposite directions cancel each other.
+pub fn signed_area(path: &[CubicBezier]) -> f32 {
+    let cross = |a: Point, b: Point| a.x * b.y - a.y * b.x;
+
+    let sum: f32 = with_closing_line(path).map(|c| {
+        6.0 * cross(c.c1, c.c2) + 3.0 * cross(c.c1, c.c3) + cross(c.c1, c.c4)
+            + 3.0 * cross(c.c2, c.c3) + 3.0 * cross(c.c2, c.c4) + 6.0 * cross(c.c3, c.c4)
+    }).sum();
+
+    sum / 20.0
+}
+
+/// Center of mass of the area enclosed by a path
+///
+/// Returns `None` if the signed area of the path is zero.
+pub fn centroid(path: &[CubicBezier]) -> Option<Point> {
+    let origin = path.first()?.c1;
+
+    // moving the path to the origin avoids precision issues
+    let shift = |p: Point| p - origin;
+    let mut area = 0.0;
+    let mut moment = Vec2::zero();
+
+    for curve in with_closing_line(path) {
+        let curve = CubicBezier {
+            c1: shift(curve.c1),
+            c2: shift(curve.c2),
+            c3: shift(curve.c3),
+            c4: shift(curve.c4),
+        };
+
+        // Green's theorem: A = ∮ x dy, ∬ x dA = ∮ x²/2 dy, ∬ y dA = -∮ y²/2 dx
+        for (t, weight) in GAUSS_LEGENDRE {
+            let p = curve.eval(t);
+            let d = curve.derivative(t);
+            area += weight * p.x * d.y;
+            moment.x += weight * 0.5 * p.x * p.x * d.y;
+            moment.y -= weight * 0.5 * p.y * p.y * d.x;
+        }
+    }
+
+    match area != 0.0 {
+        true => Some(origin + moment / area),
+        false => None,
+    }
+}
+
+/// Orientation of a path, or `None` if its signed area is zero
+pub fn orientation(path: &[CubicBezier]) -> Option<Orientation> {
+    let area = signed_area(path);
+
+    if area > 0.0 {
+        Some(Orientation::Clockwise)
+    } else if area < 0.0 {
+        Some(Orientation::CounterClockwise)
+    } else {
+        None
+    }
+}
+
+/// Reverses the direction of a path, in place
+///
+/// Connected curves stay connected.
+pub fn reverse(path: &mut [CubicBezier]) {
+    path.reverse();
+
+    for curve in path {
+        *curve = curve.reversed();
+    }
+}
+
+/// Reverses a path if it goes counter-clockwise
+///
+/// Returns true if the path was reversed.
+pub fn make_clockwise(path: &mut [CubicBezier]) -> bool {
+    let reversed = orientation(path) == Some(Orientation::CounterClockwise);
+
+    if reversed {
+        reverse(path);
+    }
+
+    reversed
+}
+
+/// Reverses a path if it goes clockwise
+///
+/// Returns true if the path was reversed.
+pub fn make_counter_clockwise(path: &mut [CubicBezier]) -> bool {
+    let reversed = orientation(path) == Some(Orientation::Clockwise);
+
+    if reversed {
+        reverse(path);
+    }
+
+    reversed
+}
+
+fn with_closing_line(path: &[CubicBezier]) -> impl Iterator<Item = CubicBezier> + '_ {
+    let closing_line = match (path.first(), path.last()) {
+        (Some(first), Some(last)) if first.c1 != last.c4 => Some(CubicBezier::segment(last.c4, first.c1)),
+        _ => None,
+    };
+
+    path.iter().copied().chain(closing_line)
+}
@@ -0,0 +1,390 @@
+//! Boolean operations on paths
+//!
+//! Operands are compound paths, as accepted by [`Canvas::fill_compound`]:
+//! each subpath must be closed. Results are made of closed subpaths which
+//! all wind in the same direction, so they can be filled with any [`FillRule`].
+
+use super::*;
+use alloc::vec::Vec;
+use flatten::distance_to_segment_sq;
+
+// distance from a curve at which its sides are sampled
+const SIDE_OFFSET: f32 = 0.01;
+
+// accuracy of the polylines used to compute winding numbers
+const WINDING_TOLERANCE: f32 = 0.001;
+
+// end points closer than this are considered connected
+const SNAP: f32 = 0.01;
+
+// splits closer than this (in `t` units) to another one or to the ends are ignored
+const MIN_T_GAP: f32 = 1e-4;
+
+/// Which parts of the operands end up in the result
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BooleanOp {
+    /// Areas covered by either operand
+    Union,
+    /// Areas covered by both operands
+    Intersection,
+    /// Areas covered by the first operand but not by the second one
+    Difference,
+    /// Areas covered by exactly one operand
+    Xor,
+}
+
+impl BooleanOp {
+    fn combine(self, in_a: bool, in_b: bool) -> bool {
+        match self {
+            Self::Union => in_a | in_b,
+            Self::Intersection => in_a & in_b,
+            Self::Difference => in_a & !in_b,
+            Self::Xor => in_a ^ in_b,
+        }
+    }
+}
+
+/// Computes `a <op> b`
+///
+/// `fill_rule` tells which areas each operand covers.
+pub fn apply(
+    a: &[&[CubicBezier]],
+    b: &[&[CubicBezier]],
+    op: BooleanOp,
+    fill_rule: FillRule,
+) -> Vec<Vec<CubicBezier>> {
+    let curves = collect_curves(a, b);
+    let pieces = split_at_intersections(&curves);
+
+    let mut kept = Vec::new();
+
+    for piece in pieces {
+        let Some(piece) = classify(piece, a, b, op, fill_rule) else {
+            continue;
+        };
+
+        if !kept.iter().any(|other| same_curve(other, &piece)) {
+            kept.push(piece);
+        }
+    }
+
+    chain(kept)
+}
+
+/// Areas covered by either operand
+pub fn union(a: &[&[CubicBezier]], b: &[&[CubicBezier]], fill_rule: FillRule) -> Vec<Vec<CubicBezier>> {
+    apply(a, b, BooleanOp::Union, fill_rule)
+}
+
+/// Areas covered by both operands
+pub fn intersection(a: &[&[CubicBezier]], b: &[&[CubicBezier]], fill_rule: FillRule) -> Vec<Vec<CubicBezier>> {
+    apply(a, b, BooleanOp::Intersection, fill_rule)
+}
+
+/// Areas covered by `a` but not by `b`
+pub fn difference(a: &[&[CubicBezier]], b: &[&[CubicBezier]], fill_rule: FillRule) -> Vec<Vec<CubicBezier>> {
+    apply(a, b, BooleanOp::Difference, fill_rule)
+}
+
+/// Areas covered by exactly one operand
+pub fn xor(a: &[&[CubicBezier]], b: &[&[CubicBezier]], fill_rule: FillRule) -> Vec<Vec<CubicBezier>> {
+    apply(a, b, BooleanOp::Xor, fill_rule)
+}
+
+fn collect_curves(a: &[&[CubicBezier]], b: &[&[CubicBezier]]) -> Vec<CubicBezier> {
+    let all = a.iter().chain(b.iter()).flat_map(|subpath| subpath.iter());
+    let is_point = |c: &&CubicBezier| c.c1 == c.c2 && c.c2 == c.c3 && c.c3 == c.c4;
+    all.filter(|c| !is_point(c)).copied().collect()
+}
+
+// splits every curve where it meets another one (or itself)
+fn split_at_intersections(curves: &[CubicBezier]) -> Vec<CubicBezier> {
+    let mut splits: Vec<Vec<f32>> = curves.iter().map(|_| Vec::new()).collect();
+    let mut found = Vec::new();
+
+    for i in 0..curves.len() {
+        for j in (i + 1)..curves.len() {
+            if !curves[i].aabb().overlaps_with(curves[j].aabb()) {
+                continue;
+            }
+
+            intersect_curves(&curves[i], &curves[j], &mut found);
+
+            for hit in found.iter() {
+                splits[i].push(hit.t1);
+                splits[j].push(hit.t2);
+            }
+        }
+
+        if let Some(hit) = self_intersection(&curves[i]) {
+            splits[i].push(hit.t1);
+            splits[i].push(hit.t2);
+        }
+    }
+
+    let mut pieces = Vec::new();
+
+    for (curve, ts) in curves.iter().zip(splits.iter_mut()) {
+        ts.sort_by(f32::total_cmp);
+
+        let mut remainder = *curve;
+        let mut prev_t = 0.0;
+
+        for t in ts.iter().copied() {
+            let too_close = (t - prev_t) < MIN_T_GAP || (1.0 - t) < MIN_T_GAP;
+
+            if too_close || !t.is_finite() {
+                continue;
+            }
+
+            let local_t = (t - prev_t) / (1.0 - prev_t);
+            let (piece, rest) = remainder.split_at(local_t);
+            push_piece(&mut pieces, piece);
+            remainder = rest;
+            prev_t = t;
+        }
+
+        push_piece(&mut pieces, remainder);
+    }
+
+    pieces
+}
+
+// The rasterizers see curves which end where they
+// start as straight, so these are cut in two.
+fn push_piece(pieces: &mut Vec<CubicBezier>, piece: CubicBezier) {
+    match piece.c1.distance_squared(piece.c4) <= SNAP * SNAP {
+        true => {
+            let (first, second) = piece.split_at(0.5);
+            pieces.push(first);
+            pieces.push(second);
+        },
+        false => pieces.push(piece),
+    }
+}
+
+// returns the piece if it's on the boundary of the result,
+// oriented so that the result is on its left side
+fn classify(
+    piece: CubicBezier,
+    a: &[&[CubicBezier]],
+    b: &[&[CubicBezier]],
+    op: BooleanOp,
+    fill_rule: FillRule,
+) -> Option<CubicBezier> {
+    let middle = piece.eval(0.5);
+    let normal = piece.normal(0.5);
+
+    if normal == Vec2::zero() {
+        return None;
+    }
+
+    let in_result = |p| {
+        let in_a = fill_rule.is_inside(precise_winding(a, p));
+        let in_b = fill_rule.is_inside(precise_winding(b, p));
+        op.combine(in_a, in_b)
+    };
+
+    let left = in_result(middle - normal * SIDE_OFFSET);
+    let right = in_result(middle + normal * SIDE_OFFSET);
+
+    match (left, right) {
+        (true, false) => Some(piece),
+        (false, true) => Some(piece.reversed()),
+        _ => None,
+    }
+}
+
+// Unlike [`winding_number`], this isn't tied to the rasterizer's
+// subdivision; points very close to the path are classified correctly.
+fn precise_winding(compound: &[&[CubicBezier]], point: Point) -> i32 {
+    let mut winding = 0;
+
+    for curve in compound.iter().flat_map(|subpath| subpath.iter()) {
+        let aabb = curve.aabb();
+
+        let outside_y = point.y < aabb.min.y || point.y > aabb.max.y;
+        if outside_y || point.x > aabb.max.x {
+            continue;
+        }
+
+        let mut start = curve.c1;
+        flatten_with(core::slice::from_ref(curve), WINDING_TOLERANCE, |end| {
+            if (start.y <= point.y) != (end.y <= point.y) {
+                let t = (point.y - start.y) / (end.y - start.y);
+                let x = start.x + (end.x - start.x) * t;
+
+                if x > point.x {
+                    winding += match end.y > start.y {
+                        true => 1,
+                        false => -1,
+                    };
+                }
+            }
+
+            start = end;
+        });
+    }
+
+    winding
+}
+
+// Both curves can have different parameterizations,
+// so the middle of `a` is compared to the whole of `b`.
+fn same_curve(a: &CubicBezier, b: &CubicBezier) -> bool {
+    let close = |p: Point, q: Point| p.distance_squared(q) <= SNAP * SNAP;
+
+    if !close(a.c1, b.c1) || !close(a.c4, b.c4) {
+        return false;
+    }
+
+    let middle = a.eval(0.5);
+    let mut start = b.c1;
+    let mut on_b = false;
+
+    flatten_with(core::slice::from_ref(b), SNAP * 0.5, |end| {
+        on_b |= distance_to_segment_sq(middle, start, end) <= SNAP * SNAP;
+        start = end;
+    });
+
+    on_b
+}
+
+// connects pieces into closed subpaths
+fn chain(mut pieces: Vec<CubicBezier>) -> Vec<Vec<CubicBezier>> {
+    let mut output = Vec::new();
+
+    while let Some(first) = pieces.pop() {
+        let mut subpath = Vec::new();
+        subpath.push(first);
+
+        loop {
+            let end = subpath.last().unwrap().c4;
+
+            if end.distance_squared(first.c1) <= SNAP * SNAP && subpath.len() > 1 {
+                break;
+            }
+
+            let closest = pieces
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (i, p.c1.distance_squared(end)))
+                .filter(|(_, d)| *d <= SNAP * SNAP)
+                .min_by(|x, y| x.1.total_cmp(&y.1));
+
+            match closest {
+                Some((i, _)) => {
+                    let mut next = pieces.swap_remove(i);
+                    next.c1 = end;
+                    subpath.push(next);
+                },
+                None => {
+                    if end.distance_squared(first.c1) > SNAP * SNAP {
+                        subpath.push(CubicBezier::segment(end, first.c1));
+                    }
+
+                    break;
+                },
+            }
+        }
+
+        // exact continuity at the junction
+        subpath.last_mut().unwrap().c4 = first.c1;
+        output.push(subpath);
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f32, y: f32, size: f32) -> Vec<CubicBezier> {
+        let mut builder = PathBuilder::new();
+        builder.move_to(Point::new(x, y));
+        builder.line_to(Point::new(x + size, y));
+        builder.line_to(Point::new(x + size, y + size));
+        builder.line_to(Point::new(x, y + size));
+        builder.build().remove(0)
+    }
+
+    // checks that subpaths are closed, returns the covered area
+    fn area(result: &[Vec<CubicBezier>]) -> f32 {
+        for subpath in result {
+            for pair in subpath.windows(2) {
+                assert_eq!(pair[0].c4, pair[1].c1);
+            }
+
+            assert!(is_closed(subpath));
+        }
+
+        result.iter().map(|subpath| analysis::signed_area(subpath)).sum::<f32>().abs()
+    }
+
+    fn check(a: &[CubicBezier], b: &[CubicBezier], expected: [f32; 4]) {
+        let ops = [BooleanOp::Union, BooleanOp::Intersection, BooleanOp::Difference, BooleanOp::Xor];
+
+        for (op, expected) in ops.into_iter().zip(expected) {
+            let result = apply(&[a], &[b], op, FillRule::NonZero);
+            let area = area(&result);
+            assert!((area - expected).abs() < 0.1, "{op:?}: {area} instead of {expected}");
+        }
+    }
+
+    #[test]
+    fn overlapping() {
+        let a = square(0.0, 0.0, 20.0);
+        let b = square(10.0, 10.0, 20.0);
+        check(&a, &b, [700.0, 100.0, 300.0, 600.0]);
+
+        let result = union(&[&a], &[&b], FillRule::NonZero);
+        assert_eq!(result.len(), 1);
+        assert!(contains(&result[0], Point::new(25.0, 25.0), FillRule::NonZero));
+        assert!(!contains(&result[0], Point::new(25.0, 5.0), FillRule::NonZero));
+    }
+
+    #[test]
+    fn shared_edge() {
+        let a = square(0.0, 0.0, 10.0);
+        let b = square(10.0, 0.0, 10.0);
+        check(&a, &b, [200.0, 0.0, 100.0, 200.0]);
+
+        // the shared edge is inside the union: it's kept once, then dropped
+        let result = union(&[&a], &[&b], FillRule::NonZero);
+        assert_eq!(result.len(), 1);
+    }
+
+    #[test]
+    fn disjoint() {
+        let a = square(0.0, 0.0, 10.0);
+        let b = square(20.0, 20.0, 10.0);
+        check(&a, &b, [200.0, 0.0, 100.0, 200.0]);
+
+        assert_eq!(union(&[&a], &[&b], FillRule::NonZero).len(), 2);
+        assert!(intersection(&[&a], &[&b], FillRule::NonZero).is_empty());
+    }
+
+    #[test]
+    fn nested() {
+        let a = square(0.0, 0.0, 30.0);
+        let b = square(10.0, 10.0, 10.0);
+        check(&a, &b, [900.0, 100.0, 800.0, 800.0]);
+
+        let result = difference(&[&a], &[&b], FillRule::NonZero);
+        let compound: Vec<&[CubicBezier]> = result.iter().map(Vec::as_slice).collect();
+        let inside = |x, y| compound.iter().map(|s| winding_number(s, Point::new(x, y))).sum::<i32>() != 0;
+        assert!(inside(5.0, 5.0) && !inside(15.0, 15.0));
+    }
+
+    #[test]
+    fn identical() {
+        let a = square(0.0, 0.0, 10.0);
+        check(&a, &a, [100.0, 100.0, 0.0, 0.0]);
+
+        // edges of both operands in the same direction are only kept once
+        let result = union(&[&a], &[&a], FillRule::NonZero);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].len(), 4);
+    }
+}
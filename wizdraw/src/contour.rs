@@ -1,31 +1,37 @@
 use super::*;
 use alloc::vec::Vec;
+use core::f32::consts::TAU;
 
-const DEG_90: f32 = core::f32::consts::PI * 0.5;
+// above this cosine, consecutive curves are considered collinear
+const COLLINEAR: f32 = 0.9999;
 
-impl CubicBezier {
-    fn reversed(self, actually: bool) -> Self {
-        match actually {
-            true => CubicBezier {
-                c1: self.c4,
-                c2: self.c3,
-                c3: self.c2,
-                c4: self.c1,
-            },
-            false => self,
-        }
-    }
+// accuracy of the arc length measurements made by `variable_contour`
+const MEASURE_TOLERANCE: f32 = 0.01;
 
-    fn offset(&self, normal_factor: f32) -> Self {
-        let side1 = travel(self.c1, self.c2, 0.5);
-        let side2 = travel(self.c2, self.c3, 0.5);
-        let side3 = travel(self.c3, self.c4, 0.5);
+// smallest part of a curve which is offset separately; this bounds the
+// number of output curves, even when the error can't be made small enough
+const MIN_SPAN: f32 = 1.0 / 1024.0;
 
-        let this_norm_c1 = (self.c2 - self.c1).normalized().rotated_z(DEG_90) * normal_factor;
-        let this_norm_c2 = (side2 - side1).normalized().rotated_z(DEG_90) * normal_factor;
-        let this_norm_c3 = (side3 - side2).normalized().rotated_z(DEG_90) * normal_factor;
-        let this_norm_c4 = (self.c4 - self.c3).normalized().rotated_z(DEG_90) * normal_factor;
+// a curve has a cusp where its speed drops below this fraction of its size
+const CUSP: f32 = 1e-3;
 
+// cusps closer than this to the ends of a curve are ignored
+const CUSP_MARGIN: f32 = 1e-3;
+
+// (index of the source curve, range of the source curve, part of the source curve)
+type Piece = (usize, [f32; 2], CubicBezier);
+
+impl CubicBezier {
+    // normal factors are interpolated between both ends
+    fn offset(&self, start_factor: f32, end_factor: f32) -> Self {
+        let factor_c2 = start_factor + (end_factor - start_factor) / 3.0;
+        let factor_c3 = end_factor + (start_factor - end_factor) / 3.0;
+
+        // zero-length handles (e.g. in straight lines) fall back to other control points
+        let this_norm_c1 = normal([self.c2 - self.c1, self.c3 - self.c1, self.c4 - self.c1]) * start_factor;
+        let this_norm_c2 = normal([self.c3 - self.c1, self.c4 - self.c1, self.c2 - self.c1]) * factor_c2;
+        let this_norm_c3 = normal([self.c4 - self.c2, self.c4 - self.c1, self.c4 - self.c3]) * factor_c3;
+        let this_norm_c4 = normal([self.c4 - self.c3, self.c4 - self.c2, self.c4 - self.c1]) * end_factor;
 
         CubicBezier {
             c1: self.c1 + this_norm_c1,
@@ -37,27 +43,24 @@ impl CubicBezier {
 
     // along normal
     fn eval_and_offset(&self, t: f32, normal_factor: f32) -> Point {
-        let side1 = travel(self.c1, self.c2, t);
-        let side2 = travel(self.c2, self.c3, t);
-        let side3 = travel(self.c3, self.c4, t);
-
-        let diag1 = travel(side1, side2, t);
-        let diag2 = travel(side2, side3, t);
-
-        let split_point = travel(diag1, diag2, t);
-        let offset = (diag2 - diag1).normalized().rotated_z(DEG_90) * normal_factor;
+        self.eval(t) + self.normal(t) * normal_factor
+    }
 
-        split_point + offset
+    // curves without any direction (points) can't be offset
+    fn is_degenerate(&self) -> bool {
+        let finite = |p: Point| p.x.is_finite() && p.y.is_finite();
+        let all_finite = finite(self.c1) && finite(self.c2) && finite(self.c3) && finite(self.c4);
+        !all_finite || self.tangent(0.0) == Vec2::zero()
     }
 
     // used by util::contour
-    fn max_offset_error(&self, offset_curve: &Self, offset: f32, steps: usize) -> f32 {
+    fn max_offset_error<F: Fn(f32) -> f32>(&self, offset_curve: &Self, offset: F, steps: usize) -> f32 {
         let step_inc = 1.0 / (steps as f32);
         let mut t = step_inc;
         let mut max_error = 0.0;
 
         for _ in 0..(steps - 1) {
-            let expected = self.eval_and_offset(t, offset);
+            let expected = self.eval_and_offset(t, offset(t));
             let actual = offset_curve.eval_and_offset(t, 0.0);
             let error = expected.distance(actual);
 
@@ -70,13 +73,59 @@ impl CubicBezier {
 
         max_error
     }
+}
 
-    fn segment(start: Point, end: Point) -> Self {
+/// Shape of the outer corner where two curves of a stroke meet
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LineJoin {
+    /// Sharp corner, replaced with a bevel if the ratio of the
+    /// miter length to the stroke width exceeds the limit
+    Miter(f32),
+    /// Circular arc centered on the corner
+    Round,
+    /// Straight line between the two offset curves
+    Bevel,
+}
+
+/// Shape of both ends of open paths
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LineCap {
+    /// The stroke stops exactly at the end of the path
+    Butt,
+    /// Half circle centered on the end of the path
+    Round,
+    /// The stroke extends past the end of the path by half its width
+    Square,
+}
+
+/// Parameters of [`contour`]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StrokeStyle {
+    /// Distance between both sides of the stroke
+    pub width: f32,
+    /// Shape of corners
+    pub join: LineJoin,
+    /// Shape of the ends of open paths
+    pub cap: LineCap,
+}
+
+impl Default for StrokeStyle {
+    /// Same as SVG's defaults
+    fn default() -> Self {
         Self {
-            c1: start,
-            c2: start,
-            c3: end,
-            c4: end,
+            width: 1.0,
+            join: LineJoin::Miter(4.0),
+            cap: LineCap::Butt,
+        }
+    }
+}
+
+impl StrokeStyle {
+    /// Default joins and caps, with a custom width
+    pub fn new(width: f32) -> Self {
+        Self {
+            width,
+            ..Default::default()
         }
     }
 }
@@ -85,7 +134,13 @@ impl CubicBezier {
 ///
 /// Input paths which don't start where they end are valid.
 ///
-/// The `width` parameter is the stroke width.
+/// The `style` parameter holds the stroke width and the shape of joins and caps.
+/// Caps are only added to open paths.
+///
+/// Degenerate input is supported: zero-length handles (e.g. from [`PathBuilder::line_to`]),
+/// zero-length curves, half turns and cusps. A path which is reduced to a single point
+/// becomes a dot, unless caps are [`LineCap::Butt`]. Curves with non-finite coordinates
+/// are skipped.
 ///
 /// The `max_error` parameter is used to check that some approximations of the
 /// implemented algorithm are correct enough; you can start with `1.0` and lower it
@@ -95,113 +150,587 @@ impl CubicBezier {
 /// bigger than the input (in number of curves), especially if `max_error` is low.
 /// With `max_error` = `1.0`, The output can typically get 2-4x bigger than the input.
 ///
+/// Inner sides of corners aren't trimmed: the offset curves on that side overlap,
+/// and they are connected through the corner, which leaves small loops. These loops
+/// are covered by [`FillRule::NonZero`], which the result must be filled with;
+/// [`FillRule::EvenOdd`] would show them as holes.
+///
 /// Author's advice: let Rust manage the vector's capacity but re-use the vector between frames.
-pub fn contour(shape: &[CubicBezier], width: f32, output: &mut Vec<CubicBezier>, max_error: f32) {
+pub fn contour(shape: &[CubicBezier], style: &StrokeStyle, output: &mut Vec<CubicBezier>, max_error: f32) {
+    stroke(shape, |_, _| style.width, style, output, max_error);
+}
+
+/// Same as [`contour`], with a stroke width which varies along the path
+///
+/// `width` is called with a fraction of the path's length, from 0 at its start
+/// to 1 at its end, and returns the stroke width at that point; `style.width`
+/// is ignored. Joins and caps adapt to the width where they are.
+///
+/// ```rust
+/// use wizdraw::{variable_contour, PathBuilder, Point, StrokeStyle};
+///
+/// let mut builder = PathBuilder::new();
+/// builder.move_to(Point::new(10.0, 10.0));
+/// builder.cubic_to(Point::new(40.0, 10.0), Point::new(60.0, 90.0), Point::new(90.0, 90.0));
+/// let path = builder.build_open();
+///
+/// // tapered stroke, from 8 pixels to 2 pixels
+/// let mut output = Vec::new();
+/// let width = |fraction: f32| 8.0 - 6.0 * fraction;
+/// variable_contour(&path[0], width, &StrokeStyle::default(), &mut output, 0.5);
+/// ```
+pub fn variable_contour<F: Fn(f32) -> f32>(
+    shape: &[CubicBezier],
+    width: F,
+    style: &StrokeStyle,
+    output: &mut Vec<CubicBezier>,
+    max_error: f32,
+) {
+    let measure = PathMeasure::new(shape, MEASURE_TOLERANCE);
+    let length = measure.length();
+
+    let width_at = |index, t| match length > 0.0 {
+        true => width(measure.distance_at(index, t) / length),
+        false => width(0.0),
+    };
+
+    stroke(shape, width_at, style, output, max_error);
+}
+
+// `width` takes the index of a curve in `shape` and a position on that curve
+fn stroke<W: Fn(usize, f32) -> f32>(
+    shape: &[CubicBezier],
+    width: W,
+    style: &StrokeStyle,
+    output: &mut Vec<CubicBezier>,
+    max_error: f32,
+) {
     output.clear();
 
-    if shape.is_empty() {
+    let Some(first) = shape.first() else {
         return;
+    };
+
+    let closed = is_closed(shape);
+
+    let outer_curves = shape.iter().copied().enumerate().flat_map(split_at_cusps);
+    let ends = offset_side(outer_curves, &width, closed, style, output, max_error);
+
+    let Some((first, last)) = ends else {
+        // no curve has a direction
+        if first.c1.x.is_finite() && first.c1.y.is_finite() {
+            dot(first.c1, width(0, 0.0), style, output, max_error);
+        }
+
+        return;
+    };
+
+    let inner_start = output.len();
+
+    // the inner side goes backwards
+    let inner_curves = shape.iter().map(CubicBezier::reversed).enumerate().rev().flat_map(split_at_cusps);
+    let inner_width = |index, t: f32| width(index, 1.0 - t);
+    offset_side(inner_curves, &inner_width, closed, style, output, max_error);
+    let inner_end = output.len();
+
+    let outer_first = output[0].c1;
+    let outer_last = output[inner_start - 1].c4;
+    let inner_first = output[inner_start].c1;
+    let inner_last = output[inner_end - 1].c4;
+
+    if closed {
+        // both sides are closed loops: go back and forth between them
+        output.push(CubicBezier::segment(outer_last, inner_first));
+    } else {
+        cap(last.c4, last.tangent(1.0), outer_last, inner_first, style, output, max_error);
     }
 
-    let closed = shape.first().unwrap().c1 == shape.last().unwrap().c4;
-    // index in output where the last curve lies
-    let mut end_index = [0; 2];
+    // move it between both sides
+    let bridge_len = output.len() - inner_end;
+    output[inner_start..].rotate_right(bridge_len);
 
-    let normal_factor = width * 0.5;
+    if closed {
+        output.push(CubicBezier::segment(inner_last, outer_first));
+    } else {
+        cap(first.c1, -first.tangent(0.0), inner_last, outer_first, style, output, max_error);
+    }
+}
 
-    for side in 0..2 {
-        // make room for end connectors
-        output.push(Default::default());
+// Splits a curve where it stops and turns back (at cusps), so
+// that offset curves don't have to follow a half turn
+fn split_at_cusps((index, curve): (usize, CubicBezier)) -> impl Iterator<Item = Piece> {
+    // derivative / 3 = a * t² + b * t + c
+    let a = curve.c4 - curve.c1 + (curve.c2 - curve.c3) * 3.0;
+    let b = (curve.c1 - curve.c2 * 2.0 + curve.c3) * 2.0;
+    let c = curve.c2 - curve.c1;
 
-        let get_curve = |i| match side {
-            0 => shape.get(i),
-            1 => shape.get(shape.len().overflowing_sub(i + 1).0),
-            _ => unreachable!(),
-        }.cloned().map(|c: CubicBezier| c.reversed(side == 1));
+    let size = curve.c1.distance(curve.c2) + curve.c2.distance(curve.c3) + curve.c3.distance(curve.c4);
+    let speed = |t: f32| (a * (t * t) + b * t + c).magnitude();
 
-        let mut previous: Option<CubicBezier> = None;
-        let mut curve_index = 0;
-        let mut maybe_curve = get_curve(curve_index);
-        let mut trial: f32 = 1.0;
+    // the speed is zero where both coordinates of the derivative are
+    let mut cusps = [1.0; 4];
+    let mut count = 0;
 
-        while let Some(rem_sc) = maybe_curve {
-            let (trial_sc, future_sc) = rem_sc.split(trial);
+    for (a, b, c) in [(a.x, b.x, c.x), (a.y, b.y, c.y)] {
+        for t in curve::solve_quadratic(a, b, c).into_iter().flatten() {
+            let inside = t > CUSP_MARGIN && t < 1.0 - CUSP_MARGIN;
 
-            let shifted = trial_sc.offset(normal_factor);
-            let max_offset_error = trial_sc.max_offset_error(&shifted, normal_factor, 8);
+            if inside && speed(t) < CUSP * size {
+                cusps[count] = t;
+                count += 1;
+            }
+        }
+    }
 
-            if max_offset_error <= max_error {
-                // previous is some for all curves but the first
-                if let Some(previous) = previous {
-                    // does it end where we start?
-                    if previous.c4 != shifted.c1 {
-                        // insert a connector to bridge previous end and next start
-                        output.push(connector(previous, shifted));
-                    }
-                }
+    cusps[..count].sort_by(f32::total_cmp);
 
-                output.push(shifted);
-                previous = Some(shifted);
+    let mut pieces = [None; 3];
+    let mut piece_count = 0;
+    let mut start = 0.0;
 
-                // did we complete this curve?
-                if trial == 1.0 {
-                    curve_index += 1;
-                    maybe_curve = get_curve(curve_index);
-                } else {
-                    maybe_curve = Some(future_sc);
-                    trial = 1.0;
+    for t in cusps[..count].iter().copied().chain([1.0]) {
+        // the last piece always goes to the end of the curve
+        if t - start < CUSP_MARGIN && t < 1.0 {
+            continue;
+        }
+
+        let mut piece = curve.subsegment(start, t);
+
+        // keep end points bit-exact
+        if start == 0.0 {
+            piece.c1 = curve.c1;
+        }
+
+        if t == 1.0 {
+            piece.c4 = curve.c4;
+        }
+
+        pieces[piece_count] = Some((index, [start, t], piece));
+        piece_count += 1;
+        start = t;
+    }
+
+    pieces.into_iter().flatten()
+}
+
+// Appends offset curves on one side of a path, with joins between them
+//
+// Returns the first and last non-degenerate parts of the path.
+fn offset_side<I, W>(
+    curves: I,
+    width: &W,
+    closed: bool,
+    style: &StrokeStyle,
+    output: &mut Vec<CubicBezier>,
+    max_error: f32,
+) -> Option<(CubicBezier, CubicBezier)>
+where
+    I: Iterator<Item = Piece>,
+    W: Fn(usize, f32) -> f32,
+{
+    let mut curves = curves.filter(|(_, _, curve)| !curve.is_degenerate());
+
+    // (source, offset curve)
+    let mut first: Option<(CubicBezier, CubicBezier)> = None;
+    let mut previous: Option<(CubicBezier, CubicBezier)> = None;
+    let mut maybe_curve = curves.next();
+    let mut trial: f32 = 1.0;
+
+    // where the remaining sub-curve starts on the piece
+    let mut rem_start = 0.0;
+
+    while let Some((index, [t0, t1], rem_sc)) = maybe_curve {
+        let (trial_sc, future_sc) = rem_sc.split_at(trial);
+
+        let trial_end = rem_start + (1.0 - rem_start) * trial;
+        let on_source = |t: f32| t0 + (t1 - t0) * (rem_start + (trial_end - rem_start) * t);
+        let normal_factor = |t: f32| width(index, on_source(t)) * 0.5;
+
+        let shifted = trial_sc.offset(normal_factor(0.0), normal_factor(1.0));
+        let max_offset_error = trial_sc.max_offset_error(&shifted, normal_factor, 8);
+
+        // tiny parts are used as is, which guarantees termination
+        if max_offset_error <= max_error || trial_end - rem_start <= MIN_SPAN {
+            // previous is some for all curves but the first
+            if let Some((prev_source, prev_shifted)) = previous {
+                // does it end where we start?
+                if prev_shifted.c4 != shifted.c1 {
+                    join(&prev_source, &trial_sc, prev_shifted.c4, shifted.c1, style, output, max_error);
                 }
+            }
+
+            output.push(shifted);
+            previous = Some((trial_sc, shifted));
+            first = first.or(previous);
 
+            // did we complete this curve?
+            if trial == 1.0 {
+                maybe_curve = curves.next();
+                rem_start = 0.0;
             } else {
-                trial *= 0.5;
+                maybe_curve = Some((index, [t0, t1], future_sc));
+                rem_start = trial_end;
+                trial = 1.0;
             }
+
+        } else {
+            trial *= 0.5;
         }
+    }
 
-        if closed {
-            output.push(Default::default());
+    if let (true, Some((first_source, first_shifted)), Some((last_source, last_shifted))) = (closed, first, previous) {
+        if last_shifted.c4 != first_shifted.c1 {
+            join(&last_source, &first_source, last_shifted.c4, first_shifted.c1, style, output, max_error);
         }
+    }
 
-        end_index[side] = output.len() - 1;
+    Some((first?.0, previous?.0))
+}
+
+// Bridges the gap between two offset curves, around the
+// point where their source curves meet
+fn join(
+    before: &CubicBezier,
+    after: &CubicBezier,
+    from: Point,
+    to: Point,
+    style: &StrokeStyle,
+    output: &mut Vec<CubicBezier>,
+    max_error: f32,
+) {
+    let pivot = after.c1;
+    let dir_in = before.tangent(1.0);
+    let dir_out = after.tangent(0.0);
+    let cos = dir_in.dot(dir_out);
+
+    if cos > COLLINEAR {
+        output.push(CubicBezier::segment(from, to));
+        return;
     }
 
-    let [outer_end_i, inner_end_i] = end_index;
+    // at a half turn, both sides are outer sides
+    let hairpin = cos < -COLLINEAR;
 
-    if closed {
-        let outer_start = output[1];
-        let outer_end = output[outer_end_i - 1];
-        output[0] = connector(outer_end, outer_start);
+    // the path turns towards this side: offset curves overlap,
+    // going through the pivot keeps the winding number positive
+    if !hairpin && (from - pivot).dot(dir_out) > 0.0 {
+        output.push(CubicBezier::segment(from, pivot));
+        output.push(CubicBezier::segment(pivot, to));
+        return;
+    }
 
-        let i = outer_end_i + 1;
-        let inner_start = output[i + 1];
-        let inner_end = output[inner_end_i - 1];
-        output[i] = connector(inner_end, inner_start);
+    // the width may vary along the path
+    let half_width = (from.distance(pivot) + to.distance(pivot)) * 0.5;
 
-        output[outer_end_i] = CubicBezier::segment(outer_end.c4, inner_end.c4);
-        output[inner_end_i] = CubicBezier::segment(inner_end.c4, outer_end.c4);
-    } else {
-        let outer_start = output[1];
-        let outer_end = output[inner_end_i];
-        output[0] = connector(outer_end, outer_start);
-
-        let i = outer_end_i + 1;
-        let inner_start = output[i + 1];
-        let inner_end = output[outer_end_i];
-        output[i] = connector(inner_end, inner_start);
-    }
-}
-
-#[inline(always)]
-fn connector(previous: CubicBezier, shifted: CubicBezier) -> CubicBezier {
-    let ctrl_len = previous.c4.distance(shifted.c1) * 0.5;
-    let ctrl_v1 = (previous.c4 - previous.c3).normalized() * ctrl_len;
-    let ctrl_v2 = (shifted.c1 - shifted.c2).normalized() * ctrl_len;
-
-    CubicBezier {
-        // extend Pc3 -> Pc4 towards Nc
-        // extend Nc2 -> Nc1 towards Pc
-        c1: previous.c4,
-        c2: previous.c4 + ctrl_v1,
-        c3: shifted.c1 + ctrl_v2,
-        c4: shifted.c1,
+    match style.join {
+        LineJoin::Miter(limit) => {
+            // miter length / width = 1 / sin(corner angle / 2)
+            let ratio = 1.0 / ((1.0 + cos) * 0.5).sqrt();
+
+            if ratio <= limit && !hairpin {
+                let direction = ((from - pivot) + (to - pivot)).normalized();
+                let tip = pivot + direction * (half_width * ratio);
+                output.push(CubicBezier::segment(from, tip));
+                output.push(CubicBezier::segment(tip, to));
+            } else {
+                output.push(CubicBezier::segment(from, to));
+            }
+        },
+        LineJoin::Round => round(pivot, from, to, dir_in, half_width, output, max_error),
+        LineJoin::Bevel => output.push(CubicBezier::segment(from, to)),
+    }
+}
+
+// Closes the stroke at an end of an open path, from one side to the other
+fn cap(
+    end: Point,
+    direction: Vec2<f32>,
+    from: Point,
+    to: Point,
+    style: &StrokeStyle,
+    output: &mut Vec<CubicBezier>,
+    max_error: f32,
+) {
+    let half_width = from.distance(to) * 0.5;
+
+    match style.cap {
+        LineCap::Butt => output.push(CubicBezier::segment(from, to)),
+        LineCap::Round => round(end, from, to, direction, half_width, output, max_error),
+        LineCap::Square => {
+            let extension = direction * half_width;
+            output.push(CubicBezier::segment(from, from + extension));
+            output.push(CubicBezier::segment(from + extension, to + extension));
+            output.push(CubicBezier::segment(to + extension, to));
+        },
+    }
+}
+
+// Zero-length paths are drawn as dots, if caps have an area
+fn dot(center: Point, width: f32, style: &StrokeStyle, output: &mut Vec<CubicBezier>, max_error: f32) {
+    let half_width = width * 0.5;
+
+    match style.cap {
+        LineCap::Butt => (),
+        LineCap::Round => {
+            let radii = Vec2::broadcast(half_width);
+            let start = output.len();
+            shapes::arc(center, radii, 0.0, 0.0, TAU, max_error, output);
+
+            // close the circle exactly
+            if output.len() > start {
+                let last = output.len() - 1;
+                output[last].c4 = output[start].c1;
+            }
+        },
+        LineCap::Square => {
+            let origin = center - Vec2::broadcast(half_width);
+            output.extend_from_slice(&shapes::rectangle(origin, Vec2::broadcast(width)));
+        },
+    }
+}
+
+// Circular arc around `center`, from `from` to `to`, going in the
+// direction of `forward`; the arc spans at most half a turn
+fn round(
+    center: Point,
+    from: Point,
+    to: Point,
+    forward: Vec2<f32>,
+    radius: f32,
+    output: &mut Vec<CubicBezier>,
+    max_error: f32,
+) {
+    let v1 = from - center;
+    let v2 = to - center;
+    let angle = (v1.x * v2.y - v1.y * v2.x).abs().atan2(v1.dot(v2));
+    let sweep = angle.copysign(Vec2::new(-v1.y, v1.x).dot(forward));
+
+    let start = output.len();
+    let radii = Vec2::broadcast(radius);
+    shapes::arc(center, radii, 0.0, v1.y.atan2(v1.x), sweep, max_error, output);
+
+    match output.len() > start {
+        true => {
+            // keep the path continuous
+            output[start].c1 = from;
+            output.last_mut().unwrap().c4 = to;
+        },
+        false => output.push(CubicBezier::segment(from, to)),
+    }
+}
+
+// first direction which isn't (almost) zero, rotated by 90 degrees
+fn normal<const N: usize>(candidates: [Vec2<f32>; N]) -> Vec2<f32> {
+    const EPSILON: f32 = 1e-12;
+
+    for v in candidates {
+        if v.magnitude_squared() > EPSILON {
+            let v = v.normalized();
+            return Vec2::new(-v.y, v.x);
+        }
+    }
+
+    Vec2::zero()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variable_width_on_lines() {
+        let mut builder = PathBuilder::new();
+        builder.move_to(Point::new(0.0, 50.0));
+        builder.line_to(Point::new(100.0, 50.0));
+        let path = builder.build_open().remove(0);
+
+        let mut output = Vec::new();
+        let width = |fraction: f32| 2.0 + 18.0 * fraction;
+        variable_contour(&path, width, &StrokeStyle::default(), &mut output, 0.01);
+
+        for x in [10.0, 50.0, 75.0] {
+            let half_width = width(x / 100.0) * 0.5;
+            let inside = |dy: f32| contains(&output, Point::new(x, 50.0 + dy), FillRule::NonZero);
+            assert!(inside(half_width - 0.1) && inside(0.1 - half_width), "{x}");
+            assert!(!inside(half_width + 0.1) && !inside(-0.1 - half_width), "{x}");
+        }
+    }
+
+    fn polyline(points: &[Point]) -> Vec<CubicBezier> {
+        let mut builder = PathBuilder::new();
+        builder.move_to(points[0]);
+
+        for point in &points[1..] {
+            builder.line_to(*point);
+        }
+
+        builder.build_open().remove(0)
+    }
+
+    fn points(output: &[CubicBezier]) -> impl Iterator<Item = Point> + '_ {
+        output.iter().flat_map(|c| [c.c1, c.c2, c.c3, c.c4])
+    }
+
+    #[test]
+    fn joins() {
+        // goes right, then up: the outer side of the corner is below and to the right
+        let pivot = Point::new(50.0, 50.0);
+        let path = polyline(&[Point::new(10.0, 50.0), pivot, Point::new(50.0, 10.0)]);
+        let mut output = Vec::new();
+
+        let stroke = |join, output: &mut Vec<CubicBezier>| {
+            let style = StrokeStyle { width: 10.0, join, cap: LineCap::Butt };
+            contour(&path, &style, output, 0.01);
+        };
+
+        let inside = |output: &Vec<CubicBezier>, x, y| contains(output, Point::new(x, y), FillRule::NonZero);
+
+        // pivot + diagonal * half_width * sqrt(2)
+        let tip = pivot + Vec2::broadcast(1.0f32).normalized() * (5.0 * 2.0f32.sqrt());
+
+        stroke(LineJoin::Miter(4.0), &mut output);
+        assert!(points(&output).any(|p| p.distance(tip) < 1e-3), "{output:?}");
+        assert!(inside(&output, 54.5, 54.5));
+
+        // the miter ratio of a right angle is sqrt(2)
+        stroke(LineJoin::Miter(1.2), &mut output);
+        assert!(points(&output).all(|p| p.distance(tip) > 1.0));
+        assert!(!inside(&output, 53.4, 53.4));
+
+        stroke(LineJoin::Bevel, &mut output);
+        assert!(inside(&output, 52.4, 52.4) && !inside(&output, 53.4, 53.4));
+
+        stroke(LineJoin::Round, &mut output);
+        assert!(inside(&output, 53.4, 53.4) && !inside(&output, 54.0, 54.0));
+    }
+
+    #[test]
+    fn sharp_miters_become_bevels() {
+        // the path turns by 160 degrees, leaving a 20 degree corner
+        let pivot = Point::new(50.0, 50.0);
+        let (sin, cos) = 20.0f32.to_radians().sin_cos();
+        let end = pivot + Vec2::new(-cos, -sin) * 40.0;
+        let path = polyline(&[Point::new(10.0, 50.0), pivot, end]);
+
+        let mut output = Vec::new();
+        let max_x = |output: &Vec<CubicBezier>| points(output).map(|p| p.x).fold(f32::MIN, f32::max);
+
+        // miter length / width = 1 / sin(10 degrees) = 5.76
+        let style = StrokeStyle { width: 10.0, join: LineJoin::Miter(4.0), cap: LineCap::Butt };
+        contour(&path, &style, &mut output, 0.01);
+        assert!(max_x(&output) < 55.01, "{}", max_x(&output));
+
+        let style = StrokeStyle { width: 10.0, join: LineJoin::Miter(6.0), cap: LineCap::Butt };
+        contour(&path, &style, &mut output, 0.01);
+        assert!(max_x(&output) > 75.0, "{}", max_x(&output));
+    }
+
+    // every point is finite and the result is a closed composite curve
+    fn assert_valid(output: &[CubicBezier]) {
+        assert!(points(output).all(|p| p.x.is_finite() && p.y.is_finite()), "{output:?}");
+
+        for pair in output.windows(2) {
+            assert_eq!(pair[0].c4, pair[1].c1);
+        }
+
+        assert!(output.is_empty() || is_closed(output));
+    }
+
+    #[test]
+    fn degenerate_input() {
+        let p = Point::new;
+        let rectangle = shapes::rectangle(p(10.0, 10.0), Vec2::new(80.0, 40.0));
+        let point = CubicBezier { c1: p(5.0, 5.0), c2: p(5.0, 5.0), c3: p(5.0, 5.0), c4: p(5.0, 5.0) };
+
+        let mut with_point = polyline(&[p(0.0, 5.0), p(5.0, 5.0)]);
+        with_point.push(point);
+        with_point.extend(polyline(&[p(5.0, 5.0), p(5.0, 10.0)]));
+
+        let hairpin = polyline(&[p(0.0, 0.0), p(10.0, 0.0), p(0.0, 0.0)]);
+        let cusp = [CubicBezier { c1: p(0.0, 0.0), c2: p(10.0, 10.0), c3: p(0.0, 10.0), c4: p(10.0, 0.0) }];
+
+        let paths: [&[CubicBezier]; 5] = [&rectangle, &[point], &with_point, &hairpin, &cusp];
+        let joins = [LineJoin::Miter(4.0), LineJoin::Miter(f32::INFINITY), LineJoin::Round, LineJoin::Bevel];
+        let caps = [LineCap::Butt, LineCap::Round, LineCap::Square];
+        let mut output = Vec::new();
+
+        for path in paths {
+            for join in joins {
+                for cap in caps {
+                    let style = StrokeStyle { width: 4.0, join, cap };
+                    contour(path, &style, &mut output, 0.01);
+                    assert_valid(&output);
+
+                    // bounded by `MIN_SPAN`
+                    assert!(output.len() < 10_000, "{}", output.len());
+                }
+            }
+        }
+
+        contour(&rectangle, &StrokeStyle::new(4.0), &mut output, 0.01);
+        assert!(contains(&output, p(10.0, 30.0), FillRule::NonZero));
+        assert!(!contains(&output, p(50.0, 30.0), FillRule::NonZero));
+
+        // the cusp is at t = 0.5
+        contour(&cusp, &StrokeStyle::new(1.0), &mut output, 0.01);
+
+        for t in [0.1, 0.3, 0.45, 0.55, 0.7, 0.9] {
+            assert!(contains(&output, cusp[0].eval(t), FillRule::NonZero), "{t}");
+        }
+    }
+
+    fn assert_bounds(output: &[CubicBezier], min: Point, max: Point) {
+        let bounds = BoundingBox::of_path(output);
+        assert!(bounds.min.distance(min) < 0.02, "{bounds:?}");
+        assert!(bounds.max.distance(max) < 0.02, "{bounds:?}");
+    }
+
+    #[test]
+    fn caps() {
+        let p = Point::new;
+        let path = polyline(&[p(10.0, 50.0), p(90.0, 50.0)]);
+        let mut output = Vec::new();
+
+        let mut stroke = |cap| {
+            let style = StrokeStyle { width: 10.0, join: LineJoin::Round, cap };
+            contour(&path, &style, &mut output, 0.01);
+            output.clone()
+        };
+
+        let inside = |output: &Vec<CubicBezier>, x, y| contains(output, p(x, y), FillRule::NonZero);
+
+        let butt = stroke(LineCap::Butt);
+        assert_bounds(&butt, p(10.0, 45.0), p(90.0, 55.0));
+
+        // extends by half the width past both ends
+        let square = stroke(LineCap::Square);
+        assert_bounds(&square, p(5.0, 45.0), p(95.0, 55.0));
+        assert!(inside(&square, 94.0, 54.0) && inside(&square, 6.0, 46.0));
+
+        // reaches half the width past both ends, but not in the corners
+        let round = stroke(LineCap::Round);
+        assert_bounds(&round, p(5.0, 45.0), p(95.0, 55.0));
+        assert!(inside(&round, 93.0, 52.0) && !inside(&round, 94.0, 54.0));
+        assert!(inside(&round, 7.0, 48.0) && !inside(&round, 6.0, 46.0));
+    }
+
+    #[test]
+    fn dots() {
+        let center = Point::new(20.0, 30.0);
+        let path = [CubicBezier { c1: center, c2: center, c3: center, c4: center }];
+        let mut output = Vec::new();
+
+        let style = StrokeStyle { width: 10.0, join: LineJoin::Round, cap: LineCap::Butt };
+        contour(&path, &style, &mut output, 0.01);
+        assert!(output.is_empty());
+
+        for cap in [LineCap::Round, LineCap::Square] {
+            let style = StrokeStyle { width: 10.0, join: LineJoin::Round, cap };
+            contour(&path, &style, &mut output, 0.01);
+            assert_valid(&output);
+            assert_bounds(&output, center - 5.0, center + 5.0);
+            assert!(contains(&output, center, FillRule::NonZero));
+            assert_eq!(contains(&output, center + 4.5, FillRule::NonZero), cap == LineCap::Square);
+        }
     }
 }
@@ -1,7 +1,9 @@
 use rgb::ComponentMap;
 use super::*;
+use segment::Curve;
 
 use alloc::{vec, vec::Vec, boxed::Box};
+use core::mem::take;
 
 mod bitmap;
 mod texture;
@@ -25,6 +27,7 @@ const DEBUG_NON_OVERLAPPING: bool = false;
 type Color16 = rgb::RGBA::<u16>;
 
 use tile::TileIterator;
+pub(crate) use tile::winding_at;
 use bitmap::Bitmaps;
 
 #[derive(Debug, Clone)]
@@ -40,9 +43,17 @@ pub struct Canvas {
     pixels: Box<[Color]>,
     mask: Box<Mask>,
     size: Vec2<usize>,
+    /// transformed curves, re-used between fills
+    scratch: Vec<CubicBezier>,
 }
 
-type Mask = [MaskRow; TILE_H];
+#[derive(Debug, Clone, Default)]
+struct Mask {
+    /// one bit per sample
+    rows: [MaskRow; TILE_H],
+    /// signed winding number of each sample, for [`FillRule::NonZero`]
+    winding: [[i16; TILE_W]; TILE_H],
+}
 
 impl Canvas {
     /// Create a basic in-memory canvas
@@ -51,8 +62,9 @@ impl Canvas {
         Canvas {
             bitmaps: Bitmaps::new(),
             pixels: vec![Default::default(); sz].into(),
-            mask: vec![0; TILE_H].try_into().unwrap(),
+            mask: Default::default(),
             size: Vec2::new(width, height),
+            scratch: Vec::new(),
         }
     }
 
@@ -60,8 +72,68 @@ impl Canvas {
         &self.pixels
     }
 
-    fn tiles(&self, ssaa: SsaaConfig) -> TileIterator {
-        TileIterator::new(self.size, ssaa)
+    fn tiles(&self, ssaa: SsaaConfig, fill_rule: FillRule) -> TileIterator {
+        TileIterator::new(self.size, ssaa, fill_rule)
+    }
+
+    fn fill_inner<C: Curve>(
+        &mut self,
+        subpaths: &[&[C]],
+        inverse: &Transform,
+        texture: &Texture,
+        fill_rule: FillRule,
+        ssaa: SsaaConfig,
+    ) {
+        let mut shape_aabb = BoundingBox::default();
+
+        for curve in subpaths.iter().copied().flatten() {
+            shape_aabb = shape_aabb.union(curve.bounds());
+        }
+
+        for mut tile in self.tiles(ssaa, fill_rule) {
+            if !shape_aabb.overlaps_with(tile.aabb) {
+                continue;
+            }
+
+            let mut curves = subpaths.iter().copied().flatten();
+            if curves.any(|c| c.overlaps(tile.aabb)) {
+                for path in subpaths {
+                    let mut last_end = path.last().map(|c| c.end());
+                    for curve in *path {
+                        assert_eq!(Some(curve.start()), last_end);
+                        last_end = Some(curve.end());
+                        tile.advance(*curve, &mut self.mask);
+                    }
+
+                    // subpaths aren't connected to each other
+                    tile.mask_pass(&mut self.mask);
+                }
+
+                tile.resolve_winding(&mut self.mask);
+
+                tile.render(
+                    &mut self.pixels,
+                    self.size,
+                    &self.mask.rows,
+                    texture,
+                    inverse,
+                    &self.bitmaps,
+                );
+
+                self.mask.rows.fill(0);
+
+            } else if tile.sample_oob(subpaths) {
+
+                tile.render_all(
+                    &mut self.pixels,
+                    self.size,
+                    texture,
+                    inverse,
+                    &self.bitmaps,
+                );
+
+            }
+        }
     }
 }
 
@@ -111,64 +183,59 @@ impl super::Canvas for Canvas {
         }
     }
 
-    fn fill_cbc(
+    fn fill_transformed(
         &mut self,
-        path: &[CubicBezier],
+        subpaths: &[&[CubicBezier]],
+        transform: &Transform,
         texture: &Texture,
+        fill_rule: FillRule,
         ssaa: SsaaConfig,
     ) {
-        let mut shape_aabb = BoundingBox::default();
+        let Some(inverse) = transform.inverse() else {
+            return;
+        };
 
-        for curve in path {
-            shape_aabb = shape_aabb.union(curve.aabb());
+        if transform.is_identity() {
+            return self.fill_inner(subpaths, &inverse, texture, fill_rule, ssaa);
         }
 
-        for mut tile in self.tiles(ssaa) {
-            if !shape_aabb.overlaps_with(tile.aabb) {
-                continue;
-            }
-
-            if path.iter().any(|c| c.overlaps(tile.aabb)) {
-                let mut last_end = path.last().map(|c| c.c4);
-                for curve in path {
-                    assert_eq!(Some(curve.c1), last_end);
-                    last_end = Some(curve.c4);
-                    tile.advance(*curve, &mut self.mask);
-                }
+        let mut scratch = take(&mut self.scratch);
+        scratch.clear();
 
-                tile.mask_pass(&mut self.mask);
+        for curve in subpaths.iter().copied().flatten() {
+            scratch.push(transform.apply_curve(curve));
+        }
 
-                tile.render(
-                    &mut self.pixels,
-                    self.size,
-                    &self.mask,
-                    texture,
-                    &self.bitmaps,
-                );
+        let mut transformed = Vec::with_capacity(subpaths.len());
+        let mut remaining = scratch.as_slice();
 
-                self.mask.fill(0);
+        for path in subpaths {
+            let (subpath, next) = remaining.split_at(path.len());
+            transformed.push(subpath);
+            remaining = next;
+        }
 
-            } else if tile.sample_oob(path) {
+        self.fill_inner(&transformed, &inverse, texture, fill_rule, ssaa);
 
-                tile.render_all(
-                    &mut self.pixels,
-                    self.size,
-                    texture,
-                    &self.bitmaps,
-                );
+        drop(transformed);
+        self.scratch = scratch;
+    }
 
-            }
-        }
+    /// Lines, quadratic curves and conics are flattened natively
+    fn fill_segments(&mut self, subpaths: &[&[Segment]], texture: &Texture, fill_rule: FillRule, ssaa: SsaaConfig) {
+        self.fill_inner(subpaths, &Transform::IDENTITY, texture, fill_rule, ssaa);
     }
 }
 
-fn is_curve_straight(curve: CubicBezier) -> bool {
+fn is_curve_straight<C: Curve>(curve: C) -> bool {
+    let (start, end) = (curve.start(), curve.end());
+
     let close_enough = |p: Point| {
         // https://en.wikipedia.org/wiki/Distance_from_a_point_to_a_line#Line_defined_by_two_points
 
-        let l = curve.c4 - curve.c1;
-        let a = l.x * (curve.c1.y - p.y);
-        let b = l.y * (curve.c1.x - p.x);
+        let l = end - start;
+        let a = l.x * (start.y - p.y);
+        let b = l.y * (start.x - p.x);
 
         // distance from p to projected point
         let distance = (a - b).abs() * fast_inv_sqrt(l.x * l.x + l.y * l.y);
@@ -176,7 +243,8 @@ fn is_curve_straight(curve: CubicBezier) -> bool {
         distance < STRAIGHT_THRESHOLD
     };
 
-    close_enough(curve.c2) && close_enough(curve.c3)
+    let [ctrl_1, ctrl_2] = curve.interior();
+    close_enough(ctrl_1) && close_enough(ctrl_2)
 }
 
 // |num| 1.0 / num.sqrt()
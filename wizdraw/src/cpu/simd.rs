@@ -1,6 +1,8 @@
 use super::*;
 
 use core::simd::prelude::*;
+// `Mask::select` is a method of this trait on recent nightlies
+use core::simd::Select;
 use core::array::from_fn;
 use vek::vec::Vec2;
 
@@ -26,13 +28,14 @@ pub fn prepare_coords(row_coords: &[IntPoint; TILE_W]) -> [SimdPoint; S_TILE_W]
     })
 }
 
-// Computes a one bit winding number increment/decrement
+type SimdMask = core::simd::Mask<i32, LANES>;
+
 #[inline(always)]
-fn simd_toggle_in_shape(
+fn simd_crossing(
     p: SimdPoint,
     s: SimdPoint,
     e: SimdPoint,
-) -> MaskRow {
+) -> (SimdMask, SimdMask) {
     let v1 = p - s;
     let v2 = e - s;
 
@@ -43,9 +46,33 @@ fn simd_toggle_in_shape(
     let dec_mask = ( cond_a) & ( cond_b) & ( cond_c);
     let inc_mask = (!cond_a) & (!cond_b) & (!cond_c);
 
+    (inc_mask, dec_mask)
+}
+
+// Computes a one bit winding number increment/decrement
+#[inline(always)]
+fn simd_toggle_in_shape(
+    p: SimdPoint,
+    s: SimdPoint,
+    e: SimdPoint,
+) -> MaskRow {
+    let (inc_mask, dec_mask) = simd_crossing(p, s, e);
     (dec_mask ^ inc_mask).to_bitmask() as MaskRow
 }
 
+// Computes signed winding number increments/decrements
+#[inline(always)]
+fn simd_winding_delta(
+    p: SimdPoint,
+    s: SimdPoint,
+    e: SimdPoint,
+) -> Simd<i16, LANES> {
+    let (inc_mask, dec_mask) = simd_crossing(p, s, e);
+    let one = SimdI32::splat(1);
+    let zero = SimdI32::splat(0);
+    (inc_mask.select(one, zero) - dec_mask.select(one, zero)).cast()
+}
+
 #[inline(always)]
 pub(super) fn process_row(
     y: usize,
@@ -68,6 +95,27 @@ pub(super) fn process_row(
     xor_mask
 }
 
+#[inline(always)]
+pub(super) fn process_row_winding(
+    y: usize,
+    simd_coords: &[SimdPoint; S_TILE_W],
+    start: IntPoint,
+    end: IntPoint,
+    winding: &mut [i16; TILE_W],
+) {
+    let row_offset = IntPoint::new(0, y as i32 * PX_WIDTH);
+    let row_offset = simd_point(row_offset);
+    let start = simd_point(start);
+    let end = simd_point(end);
+
+    for (i, chunk) in winding.chunks_exact_mut(LANES).enumerate() {
+        let shifted = simd_coords[i] + row_offset;
+        let delta = simd_winding_delta(shifted, start, end);
+        let sum = Simd::<i16, LANES>::from_slice(chunk) + delta;
+        sum.copy_to_slice(chunk);
+    }
+}
+
 #[inline(always)]
 fn simd_point(seq_p: IntPoint) -> SimdPoint {
     let x = SimdI32::splat(seq_p.x);
@@ -6,6 +6,7 @@ pub struct TileIterator {
     next: Vec2<usize>,
     tile_width: usize,
     ssaa: SsaaConfig,
+    fill_rule: FillRule,
     #[cfg(not(feature = "simd"))]
     row_coords: [IntPoint; TILE_W],
     #[cfg(feature = "simd")]
@@ -13,7 +14,7 @@ pub struct TileIterator {
 }
 
 impl TileIterator {
-    pub fn new(fb_size: Vec2<usize>, ssaa: SsaaConfig) -> Self {
+    pub fn new(fb_size: Vec2<usize>, ssaa: SsaaConfig, fill_rule: FillRule) -> Self {
         let tile_width = TILE_W / ssaa.as_mul::<usize>();
         let mut row_coords = Vec::new();
 
@@ -37,6 +38,7 @@ impl TileIterator {
             #[cfg(feature = "simd")]
             row_coords: simd::prepare_coords(&row_coords),
             ssaa,
+            fill_rule,
         }
     }
 
@@ -57,6 +59,7 @@ impl TileIterator {
             origin_f,
             tile_width: self.tile_width,
             ssaa: self.ssaa,
+            fill_rule: self.fill_rule,
             aabb,
             workspace: [Z; POINTS],
             row_coords: self.row_coords,
@@ -71,6 +74,7 @@ pub struct Tile {
     origin_f: Vec2<f32>,
     tile_width: usize,
     ssaa: SsaaConfig,
+    fill_rule: FillRule,
     pub(super) aabb: BoundingBox,
     i: usize,
     workspace: [IntPoint; POINTS],
@@ -138,76 +142,147 @@ impl Tile {
     }
 
     #[inline(always)]
-    pub fn sample_oob(&self, path: &[CubicBezier]) -> bool {
+    pub fn sample_oob<C: Curve>(&self, subpaths: &[&[C]]) -> bool {
         // sample at (1, 1)
         const POINT: IntPoint = IntPoint::new(PX_WIDTH, PX_WIDTH);
-        let mut inside = false;
+        let mut winding = 0;
 
-        for curve in path {
+        for curve in subpaths.iter().copied().flatten() {
             if self.aabb.overlaps_with(curve.aabb()) {
                 for sub_curve in curve.split_4() {
-                    let start = convert(sub_curve.c1 - self.origin_f);
-                    let end = convert(sub_curve.c4 - self.origin_f);
-                    inside ^= seq_toggle_in_shape(POINT, start, end);
+                    let start = convert(sub_curve.start() - self.origin_f);
+                    let end = convert(sub_curve.end() - self.origin_f);
+                    winding += seq_winding_delta(POINT, start, end);
                 }
             } else {
-                let start = convert(curve.c1 - self.origin_f);
-                let end = convert(curve.c4 - self.origin_f);
-                inside ^= seq_toggle_in_shape(POINT, start, end);
+                let start = convert(curve.start() - self.origin_f);
+                let end = convert(curve.end() - self.origin_f);
+                winding += seq_winding_delta(POINT, start, end);
             }
         }
 
-        inside
+        self.fill_rule.is_inside(winding)
     }
 
-    pub fn advance(&mut self, mut curve: CubicBezier, mask: &mut Mask) {
-        let mut trial: f32 = 1.0;
+    pub fn advance<C: Curve>(&mut self, curve: C, mask: &mut Mask) {
+        let aabb = self.aabb;
+        subdivide(curve, aabb, |a, b| self.line(a, b, mask));
+    }
 
-        loop {
-            let (trial_sc, future_sc) = curve.split(trial);
+    #[inline(never)] /* dbg */
+    pub fn mask_pass(&mut self, mask: &mut Mask) {
+        let mut start = self.workspace[0];
+        for p_i in 1..self.i {
+            let end = self.workspace[p_i];
 
-            let no_overlap = !self.aabb.overlaps_with(trial_sc.aabb());
-            let use_as_is = no_overlap || is_curve_straight(trial_sc);
+            match self.fill_rule {
+                FillRule::EvenOdd => for (y, row) in mask.rows.iter_mut().enumerate() {
+                    #[cfg(not(feature = "simd"))] {
+                        *row ^= seq_process_row(y, &self.row_coords, start, end)
+                    }
 
-            if use_as_is {
+                    #[cfg(feature = "simd")] {
+                        *row ^= simd::process_row(y, &self.row_coords, start, end)
+                    }
+                },
+                FillRule::NonZero => for (y, row) in mask.winding.iter_mut().enumerate() {
+                    #[cfg(not(feature = "simd"))] {
+                        seq_process_row_winding(y, &self.row_coords, start, end, row)
+                    }
 
-                self.line(trial_sc.c1, trial_sc.c4, mask);
+                    #[cfg(feature = "simd")] {
+                        simd::process_row_winding(y, &self.row_coords, start, end, row)
+                    }
+                },
+            }
 
-                // did we complete this curve?
-                if trial == 1.0 {
-                    break;
-                }
+            start = end;
+        }
 
-                curve = future_sc;
-                trial = 1.0;
+        self.i = 0;
+    }
 
-            } else {
-                trial *= 0.5;
+    /// Sets mask bits of samples with a non-zero winding number
+    ///
+    /// Winding numbers are reset in the process.
+    pub fn resolve_winding(&self, mask: &mut Mask) {
+        if self.fill_rule != FillRule::NonZero {
+            return;
+        }
+
+        for (row, winding) in mask.rows.iter_mut().zip(mask.winding.iter_mut()) {
+            for (i, sample) in winding.iter_mut().enumerate() {
+                *row |= ((*sample != 0) as MaskRow) << i;
+                *sample = 0;
             }
         }
     }
+}
 
-    #[inline(never)] /* dbg */
-    pub fn mask_pass(&mut self, mask: &mut Mask) {
-        let mut start = self.workspace[0];
-        for p_i in 1..self.i {
-            let end = self.workspace[p_i];
+// Splits a curve until all parts overlapping the tile are straight
+#[inline(always)]
+fn subdivide<C: Curve, F: FnMut(Point, Point)>(mut curve: C, aabb: BoundingBox, mut line: F) {
+    let mut trial: f32 = 1.0;
 
-            for (y, row) in mask.iter_mut().enumerate() {
-                #[cfg(not(feature = "simd"))] {
-                    *row ^= seq_process_row(y, &self.row_coords, start, end)
-                }
+    loop {
+        let (trial_sc, future_sc) = curve.split_at(trial);
 
-                #[cfg(feature = "simd")] {
-                    *row ^= simd::process_row(y, &self.row_coords, start, end)
-                }
+        let no_overlap = !aabb.overlaps_with(trial_sc.aabb());
+        let use_as_is = no_overlap || is_curve_straight(trial_sc);
+
+        if use_as_is {
+
+            line(trial_sc.start(), trial_sc.end());
+
+            // did we complete this curve?
+            if trial == 1.0 {
+                break;
             }
 
-            start = end;
+            curve = future_sc;
+            trial = 1.0;
+
+        } else {
+            trial *= 0.5;
         }
+    }
+}
 
-        self.i = 0;
+/// Winding number of a path around `point`, as seen by the rasterizer
+///
+/// The path is processed like it would be for the tile containing `point`
+/// with [`SsaaConfig::None`], so results match rendered pixels exactly.
+pub fn winding_at(path: &[CubicBezier], point: Point) -> i32 {
+    let tile_size = Vec2::new(TILE_W as f32, TILE_H as f32);
+    let origin = (point / tile_size).map(|f| f.floor()) * tile_size;
+    let aabb = BoundingBox::new(origin, origin + tile_size);
+    let sample = convert(point - origin);
+
+    let mut winding = 0;
+    let mut line = |a: Point, b: Point| {
+        let start = convert(a - origin);
+        let end = convert(b - origin);
+        winding += seq_winding_delta(sample, start, end);
+    };
+
+    if path.iter().any(|c| c.overlaps(aabb)) {
+        for curve in path {
+            subdivide(*curve, aabb, &mut line);
+        }
+    } else {
+        // same as Tile::sample_oob
+        for curve in path {
+            if aabb.overlaps_with(curve.aabb()) {
+                for sub_curve in curve.split_4() {
+                    line(sub_curve.c1, sub_curve.c4);
+                }
+            } else {
+                line(curve.c1, curve.c4);
+            }
+        }
     }
+
+    winding
 }
 
 #[cfg(not(feature = "simd"))]
@@ -230,9 +305,25 @@ fn seq_process_row(
     xor_mask
 }
 
-// Computes a one bit winding number increment/decrement
+#[cfg(not(feature = "simd"))]
 #[inline(always)]
-fn seq_toggle_in_shape(point: IntPoint, a: IntPoint, b: IntPoint) -> bool {
+fn seq_process_row_winding(
+    y: usize,
+    row_coords: &[IntPoint; TILE_W],
+    start: IntPoint,
+    end: IntPoint,
+    winding: &mut [i16; TILE_W],
+) {
+    let row_offset = IntPoint::new(0, y as i32 * PX_WIDTH);
+
+    for i in 0..TILE_W {
+        let shifted = row_offset + row_coords[i];
+        winding[i] += seq_winding_delta(shifted, start, end) as i16;
+    }
+}
+
+#[inline(always)]
+fn seq_crossing(point: IntPoint, a: IntPoint, b: IntPoint) -> (bool, bool) {
     let v1 = point - a;
     let v2 = b - a;
 
@@ -243,17 +334,33 @@ fn seq_toggle_in_shape(point: IntPoint, a: IntPoint, b: IntPoint) -> bool {
     let dec = ( crit_1) & ( crit_2) & ( crit_3);
     let inc = (!crit_1) & (!crit_2) & (!crit_3);
 
+    (inc, dec)
+}
+
+// Computes a one bit winding number increment/decrement
+#[cfg(not(feature = "simd"))]
+#[inline(always)]
+fn seq_toggle_in_shape(point: IntPoint, a: IntPoint, b: IntPoint) -> bool {
+    let (inc, dec) = seq_crossing(point, a, b);
     inc != dec
 }
 
+// Computes a signed winding number increment/decrement
+#[inline(always)]
+fn seq_winding_delta(point: IntPoint, a: IntPoint, b: IntPoint) -> i32 {
+    let (inc, dec) = seq_crossing(point, a, b);
+    (inc as i32) - (dec as i32)
+}
+
 // rendering
 impl Tile {
     pub fn render(
         &mut self,
         pixels: &mut [Color],
         fb_size: Vec2<usize>,
-        mask: &Mask,
+        mask: &[MaskRow; TILE_H],
         texture: &Texture,
+        inverse: &Transform,
         bitmaps: &Bitmaps,
     ) {
         let offsets = self.ssaa.offsets();
@@ -288,7 +395,7 @@ impl Tile {
                 for offset in offsets {
                     if (row & 1) > 0 {
                         let point = subp_base + Vec2::from(*offset);
-                        let sample = texture.sample(point, bitmaps);
+                        let sample = texture.sample(inverse.apply(point), bitmaps);
                         color += sample;
                         hits = true;
                     }
@@ -313,6 +420,7 @@ impl Tile {
         pixels: &mut [Color],
         fb_size: Vec2<usize>,
         mut texture: &Texture,
+        inverse: &Transform,
         bitmaps: &Bitmaps,
     ) {
         let offsets = self.ssaa.offsets();
@@ -347,7 +455,7 @@ impl Tile {
 
                 for offset in offsets {
                     let point = subp_base + Vec2::from(*offset);
-                    let sample = texture.sample(point, bitmaps);
+                    let sample = texture.sample(inverse.apply(point), bitmaps);
                     color += sample;
                 }
 
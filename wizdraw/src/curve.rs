@@ -0,0 +1,184 @@
+use super::*;
+
+impl CubicBezier {
+    /// Point of the curve at `t`, which usually lies in `0.0..=1.0`
+    pub fn eval(&self, t: f32) -> Point {
+        self.blossom(t, t, t)
+    }
+
+    /// First derivative (velocity) at `t`
+    pub fn derivative(&self, t: f32) -> Vec2<f32> {
+        let side1 = travel(self.c1, self.c2, t);
+        let side2 = travel(self.c2, self.c3, t);
+        let side3 = travel(self.c3, self.c4, t);
+
+        let diag1 = travel(side1, side2, t);
+        let diag2 = travel(side2, side3, t);
+
+        (diag2 - diag1) * 3.0
+    }
+
+    /// Second derivative (acceleration) at `t`
+    pub fn second_derivative(&self, t: f32) -> Vec2<f32> {
+        let side1 = self.c2 - self.c1;
+        let side2 = self.c3 - self.c2;
+        let side3 = self.c4 - self.c3;
+
+        let diag1 = travel(side1, side2, t);
+        let diag2 = travel(side2, side3, t);
+
+        (diag2 - diag1) * 6.0
+    }
+
+    /// Unit vector in the direction of travel at `t`
+    ///
+    /// Where the derivative vanishes (e.g. at `t = 0` when `c1 == c2`), the
+    /// direction of the curve right after/before that point is used instead.
+    /// Returns a zero vector if all control points are equal.
+    pub fn tangent(&self, t: f32) -> Vec2<f32> {
+        const EPSILON: f32 = 1e-12;
+
+        let candidates = [
+            self.derivative(t),
+            // limit direction at a cusp or an endpoint with a zero-length handle
+            self.second_derivative(t) * (0.5 - t).signum(),
+            match t < 0.5 {
+                true => self.c3 - self.c1,
+                false => self.c4 - self.c2,
+            },
+            self.c4 - self.c1,
+        ];
+
+        for v in candidates {
+            if v.magnitude_squared() > EPSILON {
+                return v.normalized();
+            }
+        }
+
+        Vec2::zero()
+    }
+
+    /// Unit vector perpendicular to [`CubicBezier::tangent`]
+    ///
+    /// This is the tangent rotated by +90 degrees, i.e. it points to the
+    /// right of the direction of travel when the Y axis points down.
+    pub fn normal(&self, t: f32) -> Vec2<f32> {
+        let tangent = self.tangent(t);
+        Vec2::new(-tangent.y, tangent.x)
+    }
+
+    /// Signed curvature at `t` (the inverse of the radius of the osculating circle)
+    ///
+    /// The sign is positive when the curve turns towards [`CubicBezier::normal`].
+    /// Returns zero where the derivative vanishes.
+    pub fn curvature(&self, t: f32) -> f32 {
+        let d1 = self.derivative(t);
+        let d2 = self.second_derivative(t);
+        let speed_sq = d1.magnitude_squared();
+
+        if speed_sq == 0.0 {
+            return 0.0;
+        }
+
+        let cross = d1.x * d2.y - d1.y * d2.x;
+        cross / (speed_sq * speed_sq.sqrt())
+    }
+
+    /// Part of the curve between `t0` and `t1`
+    ///
+    /// If `t1 < t0`, the resulting curve goes backwards.
+    pub fn subsegment(&self, t0: f32, t1: f32) -> Self {
+        Self {
+            c1: self.blossom(t0, t0, t0),
+            c2: self.blossom(t0, t0, t1),
+            c3: self.blossom(t0, t1, t1),
+            c4: self.blossom(t1, t1, t1),
+        }
+    }
+
+    /// Same curve, traversed from `c4` to `c1`
+    pub fn reversed(&self) -> Self {
+        Self {
+            c1: self.c4,
+            c2: self.c3,
+            c3: self.c2,
+            c4: self.c1,
+        }
+    }
+
+    // https://en.wikipedia.org/wiki/Blossom_(functional)
+    fn blossom(&self, u: f32, v: f32, w: f32) -> Point {
+        let side1 = travel(self.c1, self.c2, u);
+        let side2 = travel(self.c2, self.c3, u);
+        let side3 = travel(self.c3, self.c4, u);
+
+        let diag1 = travel(side1, side2, v);
+        let diag2 = travel(side2, side3, v);
+
+        travel(diag1, diag2, w)
+    }
+
+    /// Smallest axis-aligned box containing the curve
+    ///
+    /// Unlike the box of the four control points, this one touches the curve
+    /// on each side: extrema are found where the derivative is zero.
+    pub fn tight_bounds(&self) -> BoundingBox {
+        let mut aabb = BoundingBox::new(self.c1, self.c1).include(self.c4);
+
+        // derivative / 3 = a * t² + b * t + c
+        let a = self.c4 - self.c1 + (self.c2 - self.c3) * 3.0;
+        let b = (self.c1 - self.c2 * 2.0 + self.c3) * 2.0;
+        let c = self.c2 - self.c1;
+
+        for (a, b, c) in [(a.x, b.x, c.x), (a.y, b.y, c.y)] {
+            for t in solve_quadratic(a, b, c).into_iter().flatten() {
+                if t > 0.0 && t < 1.0 {
+                    aabb = aabb.include(self.eval(t));
+                }
+            }
+        }
+
+        aabb
+    }
+}
+
+/// Real roots of `a * x² + b * x + c`
+///
+/// Degenerate (linear) equations have at most one root.
+pub(crate) fn solve_quadratic(a: f32, b: f32, c: f32) -> [Option<f32>; 2] {
+    const EPSILON: f32 = 1e-6;
+
+    let scale = a.abs().max(b.abs()).max(c.abs());
+
+    if scale == 0.0 {
+        return [None, None];
+    }
+
+    if a.abs() <= EPSILON * scale {
+        return match b.abs() <= EPSILON * scale {
+            true => [None, None],
+            false => [Some(-c / b), None],
+        };
+    }
+
+    let discriminant = b * b - 4.0 * a * c;
+
+    if discriminant < 0.0 {
+        return [None, None];
+    }
+
+    // numerically stable variant
+    let sqrt = match b < 0.0 {
+        true => -discriminant.sqrt(),
+        false => discriminant.sqrt(),
+    };
+
+    let q = -0.5 * (b + sqrt);
+    let root_1 = q / a;
+    let root_2 = match q == 0.0 {
+        true => root_1,
+        false => c / q,
+    };
+
+    [Some(root_1), Some(root_2)]
+}
@@ -0,0 +1,248 @@
+use super::*;
+use alloc::vec::Vec;
+
+// accuracy of the arc length measurements
+const DASH_TOLERANCE: f32 = 0.01;
+
+// patterns whose cycle is shorter than this fraction of the path's length
+// are finer than float precision along the path
+const FINEST_PATTERN: f32 = 1e-6;
+
+/// Splits a path into dashes
+///
+/// `pattern` holds the lengths of dashes and gaps, alternately, starting with
+/// a dash; if it has an odd number of lengths, it is repeated to yield an even
+/// number, like in SVG. `phase` is the distance into the pattern at which the
+/// path starts. Dashes of zero length are skipped.
+///
+/// If the pattern is empty, invalid (negative lengths, zero sum) or too fine
+/// (a cycle shorter than a millionth of the path's length), the whole path is
+/// a single dash.
+///
+/// On closed paths, the dash which goes through the start of the path is kept
+/// in one piece. Each dash is a valid composite bezier curve, which can be passed
+/// to `contour`.
+///
+/// Vectors already in `output` are cleared and re-used, so dashing the same
+/// path repeatedly (e.g. to animate the phase) doesn't allocate.
+pub fn dash(path: &[CubicBezier], pattern: &[f32], phase: f32, output: &mut Vec<Vec<CubicBezier>>) {
+    let mut count = 0;
+
+    if !path.is_empty() {
+        let measure = PathMeasure::new(path, DASH_TOLERANCE);
+
+        dash_ranges(measure.length(), is_closed(path), pattern, phase, |from, to, append| {
+            if !append {
+                match output.get_mut(count) {
+                    Some(dash) => dash.clear(),
+                    None => output.push(Vec::new()),
+                }
+
+                count += 1;
+            }
+
+            measure.extract(from, to, &mut output[count - 1]);
+        });
+    }
+
+    output.truncate(count);
+}
+
+/// Splits a path into dashes and strokes each of them
+///
+/// See [`dash`] for `pattern` and `phase` and [`contour`] for the other
+/// parameters. Caps are applied to both ends of each dash.
+///
+/// `output` is a compound path, to be filled with [`Canvas::fill_compound`]
+/// and [`FillRule::NonZero`]. `scratch` holds intermediate curves; its content
+/// is unspecified afterwards. If the same `output` and `scratch` are passed on
+/// every call (e.g. to animate the phase), this doesn't allocate once their
+/// capacities are large enough.
+#[cfg(any(doc, feature = "contour"))]
+pub fn dash_contour(
+    shape: &[CubicBezier],
+    pattern: &[f32],
+    phase: f32,
+    style: &StrokeStyle,
+    output: &mut Vec<Vec<CubicBezier>>,
+    scratch: &mut Vec<CubicBezier>,
+    max_error: f32,
+) {
+    dash(shape, pattern, phase, output);
+
+    for dash in output.iter_mut() {
+        contour(dash, style, scratch, max_error);
+
+        // the dash's vector becomes the scratch buffer of the next stroke
+        core::mem::swap(dash, scratch);
+    }
+}
+
+// Calls `callback` with the start and end distance of each dash; if the
+// last argument is true, the range continues the previous dash.
+fn dash_ranges<F: FnMut(f32, f32, bool)>(length: f32, closed: bool, pattern: &[f32], phase: f32, mut callback: F) {
+    let valid = pattern.iter().all(|l| *l >= 0.0);
+    let mut total: f32 = pattern.iter().sum();
+
+    // odd patterns are repeated once
+    let cycle = match pattern.len() % 2 {
+        0 => pattern.len(),
+        _ => pattern.len() * 2,
+    };
+
+    total *= (cycle / pattern.len().max(1)) as f32;
+
+    if !valid || total.is_nan() || total <= 0.0 || total < length * FINEST_PATTERN {
+        callback(0.0, length, false);
+        return;
+    }
+
+    // find where the path starts in the pattern
+    let mut k = 0;
+    let mut offset = phase % total;
+
+    if offset < 0.0 {
+        offset += total;
+    }
+
+    while k < cycle && offset >= pattern[k % pattern.len()] {
+        offset -= pattern[k % pattern.len()];
+        k += 1;
+    }
+
+    let mut start = -offset;
+    let mut first_dash = None;
+
+    // lengths below the float step at `start` don't move it
+    let mut cycle_start = start;
+    let mut steps = 0;
+
+    while start < length {
+        let end = start + pattern[k % pattern.len()];
+        let from = start.max(0.0);
+        let to = end.min(length);
+
+        if k % 2 == 0 && from < to {
+            match (closed, from == 0.0) {
+                // may continue the last dash
+                (true, true) => first_dash = Some(to),
+                _ => callback(from, to, false),
+            }
+        }
+
+        start = end;
+        k = (k + 1) % cycle;
+        steps += 1;
+
+        if steps == cycle {
+            if start <= cycle_start {
+                break;
+            }
+
+            cycle_start = start;
+            steps = 0;
+        }
+    }
+
+    if let Some(to) = first_dash {
+        // did the last dash reach the end of the path?
+        let append = k % 2 == 1 && to < length;
+        callback(0.0, to, append);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(size: f32) -> Vec<CubicBezier> {
+        let mut builder = PathBuilder::new();
+        builder.move_to(Point::zero());
+        builder.line_to(Point::new(size, 0.0));
+        builder.line_to(Point::new(size, size));
+        builder.line_to(Point::new(0.0, size));
+        builder.build().remove(0)
+    }
+
+    fn length(dash: &[CubicBezier]) -> f32 {
+        PathMeasure::new(dash, DASH_TOLERANCE).length()
+    }
+
+    #[test]
+    fn closed_path() {
+        let path = square(10.0);
+        let mut dashes = Vec::new();
+
+        dash(&path, &[6.0, 4.0], 0.0, &mut dashes);
+        assert_eq!(dashes.len(), 4);
+
+        for dash in &dashes {
+            assert!((length(dash) - 6.0).abs() < 0.02, "{}", length(dash));
+        }
+
+        dash(&path, &[6.0, 4.0], 7.5, &mut dashes);
+        assert!(dashes[0][0].c1.distance(Point::new(2.5, 0.0)) < 0.02, "{:?}", dashes[0][0]);
+    }
+
+    #[test]
+    fn open_path() {
+        let mut builder = PathBuilder::new();
+        builder.move_to(Point::zero());
+        builder.line_to(Point::new(20.0, 0.0));
+        let path = builder.build_open().remove(0);
+
+        let mut dashes = Vec::new();
+        dash(&path, &[5.0], 2.0, &mut dashes);
+
+        let expected = [(0.0, 3.0), (8.0, 13.0), (18.0, 20.0)];
+        assert_eq!(dashes.len(), expected.len());
+
+        for (dash, (from, to)) in dashes.iter().zip(expected) {
+            assert!((dash[0].c1.x - from).abs() < 0.02, "{dash:?}");
+            assert!((dash.last().unwrap().c4.x - to).abs() < 0.02, "{dash:?}");
+        }
+    }
+
+    #[test]
+    fn fine_patterns_terminate() {
+        let path = square(500.0);
+        let mut dashes = Vec::new();
+
+        dash(&path, &[1e-5, 1e-5], 0.0, &mut dashes);
+        assert_eq!(dashes.len(), 1);
+        assert_eq!(dashes[0].len(), path.len());
+    }
+
+    #[cfg(feature = "contour")]
+    #[test]
+    fn strokes_reuse_buffers() {
+        let mut builder = PathBuilder::new();
+        builder.move_to(Point::new(0.0, 10.0));
+        builder.line_to(Point::new(40.0, 10.0));
+        let path = builder.build_open().remove(0);
+
+        let style = StrokeStyle::new(4.0);
+        let mut output = Vec::new();
+        let mut scratch = Vec::new();
+        dash_contour(&path, &[5.0, 5.0], 0.0, &style, &mut output, &mut scratch, 0.01);
+        assert_eq!(output.len(), 4);
+
+        let inside = |x| output.iter().any(|stroke| contains(stroke, Point::new(x, 10.0), FillRule::NonZero));
+
+        for x in [2.5, 12.5, 22.5, 32.5] {
+            assert!(inside(x), "{x}");
+            assert!(!inside(x + 5.0), "{x}");
+        }
+
+        // the same buffers are used on the next call
+        let buffers = |output: &Vec<Vec<CubicBezier>>, scratch: &Vec<CubicBezier>| {
+            let mut pointers: Vec<_> = output.iter().chain([scratch]).map(|v| (v.as_ptr(), v.capacity())).collect();
+            pointers.sort();
+            pointers
+        };
+
+        let before = buffers(&output, &scratch);
+        dash_contour(&path, &[5.0, 5.0], 0.0, &style, &mut output, &mut scratch, 0.01);
+        assert_eq!(buffers(&output, &scratch), before);
+    }
+}
@@ -0,0 +1,334 @@
+use super::*;
+use alloc::vec::Vec;
+
+// points are split at the worst fit rather than halfway,
+// so this is deeper than the crate's `MAX_DEPTH`
+const MAX_SPLITS: usize = 32;
+
+// fits worse than `max_error` times this are split right away
+const REPARAMETERIZE_FACTOR: f32 = 4.0;
+
+const NEWTON_ITERATIONS: usize = 4;
+
+// turns are measured between chords at least this many times `max_error`
+// long, so that noise in the input isn't mistaken for corners
+const CORNER_WINDOW: f32 = 4.0;
+
+/// Fits a composite bezier curve to a sequence of points
+///
+/// This is Philip J. Schneider's algorithm, from "An Algorithm for Automatically
+/// Fitting Digitized Curves" (Graphics Gems, 1990). Every point is at most
+/// `max_error` away from the resulting path, which starts at the first point
+/// and ends at the last one.
+///
+/// The path is smooth, except at corners: points where the polyline turns
+/// by more than `corner_angle` (in radians). Turns are measured between chords
+/// which span a few times `max_error`, so noisy input only has corners where
+/// the polyline really changes direction.
+///
+/// To get a closed path, repeat the first point at the end.
+///
+/// Previous content of `output` is cleared.
+pub fn fit_curve(points: &[Point], max_error: f32, corner_angle: f32, output: &mut Vec<CubicBezier>) {
+    output.clear();
+
+    let mut unique = Vec::with_capacity(points.len());
+    for point in points {
+        if unique.last() != Some(point) {
+            unique.push(*point);
+        }
+    }
+
+    if unique.len() < 2 {
+        return;
+    }
+
+    let max_error_sq = max_error * max_error;
+    let corners = find_corners(&unique, max_error * CORNER_WINDOW, corner_angle.cos());
+    let mut start = 0;
+
+    for i in 1..unique.len() {
+        let is_last = i == unique.len() - 1;
+
+        if corners[i] || is_last {
+            let run = &unique[start..=i];
+            let start_tangent = (run[1] - run[0]).normalized();
+            let end_tangent = (run[run.len() - 2] - run[run.len() - 1]).normalized();
+            fit_cubic(run, start_tangent, end_tangent, max_error_sq, 0, output);
+            start = i;
+        }
+    }
+}
+
+// A point is a corner if the chords to the points `window` away on both
+// sides make a sharp turn, and if that turn is the sharpest in the window.
+fn find_corners(points: &[Point], window: f32, min_cos: f32) -> Vec<bool> {
+    // distance along the polyline
+    let mut distances = Vec::with_capacity(points.len());
+    let mut distance = 0.0;
+    let mut prev = points[0];
+
+    for point in points {
+        distance += prev.distance(*point);
+        distances.push(distance);
+        prev = *point;
+    }
+
+    let last = points.len() - 1;
+
+    // cosine of the turn at each point, 1.0 meaning straight
+    let turns: Vec<f32> = (0..points.len()).map(|i| match i == 0 || i == last {
+        true => 1.0,
+        false => {
+            let before = distances.partition_point(|d| *d <= distances[i] - window).saturating_sub(1);
+            let after = distances.partition_point(|d| *d < distances[i] + window).min(last);
+            let incoming = (points[i] - points[before]).normalized();
+            let outgoing = (points[after] - points[i]).normalized();
+            incoming.dot(outgoing)
+        },
+    }).collect();
+
+    (0..points.len()).map(|i| {
+        let from = distances.partition_point(|d| *d < distances[i] - window);
+        let to = distances.partition_point(|d| *d <= distances[i] + window);
+
+        // the first of equally sharp turns wins
+        let sharpest = turns[from..i].iter().all(|t| *t > turns[i])
+            && turns[i..to].iter().all(|t| *t >= turns[i]);
+
+        turns[i] < min_cos && sharpest
+    }).collect()
+}
+
+// `start_tangent` points into the curve at its start,
+// `end_tangent` points into it at its end.
+fn fit_cubic(
+    points: &[Point],
+    start_tangent: Vec2<f32>,
+    end_tangent: Vec2<f32>,
+    max_error_sq: f32,
+    depth: usize,
+    output: &mut Vec<CubicBezier>,
+) {
+    let first = points[0];
+    let last = points[points.len() - 1];
+
+    if points.len() == 2 {
+        let handle = first.distance(last) / 3.0;
+
+        output.push(CubicBezier {
+            c1: first,
+            c2: first + start_tangent * handle,
+            c3: last + end_tangent * handle,
+            c4: last,
+        });
+
+        return;
+    }
+
+    let mut params = chord_length_parameterize(points);
+    let mut curve = generate_bezier(points, &params, start_tangent, end_tangent);
+    let (mut error_sq, mut split) = max_error(points, &params, &curve);
+
+    if error_sq > max_error_sq && error_sq < max_error_sq * REPARAMETERIZE_FACTOR * REPARAMETERIZE_FACTOR {
+        for _ in 0..NEWTON_ITERATIONS {
+            reparameterize(points, &mut params, &curve);
+            curve = generate_bezier(points, &params, start_tangent, end_tangent);
+            (error_sq, split) = max_error(points, &params, &curve);
+
+            if error_sq <= max_error_sq {
+                break;
+            }
+        }
+    }
+
+    if error_sq <= max_error_sq || depth == MAX_SPLITS {
+        output.push(curve);
+        return;
+    }
+
+    let mut center_tangent = (points[split - 1] - points[split + 1]).normalized();
+
+    // the neighbours of `split` can be on the same side of it
+    if !center_tangent.x.is_finite() || center_tangent == Vec2::zero() {
+        center_tangent = (points[split - 1] - points[split]).normalized();
+    }
+
+    // both halves contain the split point, so they are connected
+    fit_cubic(&points[..=split], start_tangent, center_tangent, max_error_sq, depth + 1, output);
+    fit_cubic(&points[split..], -center_tangent, end_tangent, max_error_sq, depth + 1, output);
+}
+
+// assigns a parameter to each point, proportionally to the polyline's length
+fn chord_length_parameterize(points: &[Point]) -> Vec<f32> {
+    let mut params = Vec::with_capacity(points.len());
+    let mut length = 0.0;
+    let mut prev = points[0];
+
+    for point in points {
+        length += prev.distance(*point);
+        params.push(length);
+        prev = *point;
+    }
+
+    for param in params.iter_mut() {
+        *param /= length;
+    }
+
+    params
+}
+
+// least-squares fit of the two handle lengths, end points and tangents being fixed
+fn generate_bezier(points: &[Point], params: &[f32], start_tangent: Vec2<f32>, end_tangent: Vec2<f32>) -> CubicBezier {
+    let first = points[0];
+    let last = points[points.len() - 1];
+
+    let mut c = [[0.0f32; 2]; 2];
+    let mut x = [0.0f32; 2];
+
+    for (point, u) in points.iter().zip(params) {
+        let mu = 1.0 - u;
+        let b0 = mu * mu * mu;
+        let b1 = 3.0 * u * mu * mu;
+        let b2 = 3.0 * u * u * mu;
+        let b3 = u * u * u;
+
+        let a1 = start_tangent * b1;
+        let a2 = end_tangent * b2;
+
+        c[0][0] += a1.dot(a1);
+        c[0][1] += a1.dot(a2);
+        c[1][1] += a2.dot(a2);
+
+        let tmp = *point - (first * (b0 + b1) + last * (b2 + b3));
+        x[0] += a1.dot(tmp);
+        x[1] += a2.dot(tmp);
+    }
+
+    c[1][0] = c[0][1];
+
+    let det_c = c[0][0] * c[1][1] - c[1][0] * c[0][1];
+    let det_c0_x = c[0][0] * x[1] - c[1][0] * x[0];
+    let det_x_c1 = x[0] * c[1][1] - x[1] * c[0][1];
+
+    let (mut alpha_l, mut alpha_r) = match det_c == 0.0 {
+        true => (0.0, 0.0),
+        false => (det_x_c1 / det_c, det_c0_x / det_c),
+    };
+
+    // Wu/Barsky heuristic: fall back to a third
+    // of the chord on degenerate solutions
+    let chord = first.distance(last);
+    let epsilon = 1e-6 * chord;
+
+    if !(alpha_l > epsilon && alpha_r > epsilon) {
+        alpha_l = chord / 3.0;
+        alpha_r = alpha_l;
+    }
+
+    CubicBezier {
+        c1: first,
+        c2: first + start_tangent * alpha_l,
+        c3: last + end_tangent * alpha_r,
+        c4: last,
+    }
+}
+
+// returns the largest squared distance and the index of the matching point
+fn max_error(points: &[Point], params: &[f32], curve: &CubicBezier) -> (f32, usize) {
+    let mut max_sq = 0.0;
+    let mut split = points.len() / 2;
+
+    // the end points are always exact
+    for i in 1..(points.len() - 1) {
+        let distance_sq = curve.eval(params[i]).distance_squared(points[i]);
+
+        if distance_sq >= max_sq {
+            max_sq = distance_sq;
+            split = i;
+        }
+    }
+
+    (max_sq, split)
+}
+
+// Newton-Raphson step on each parameter, towards the closest point of the curve
+fn reparameterize(points: &[Point], params: &mut [f32], curve: &CubicBezier) {
+    for (point, u) in points.iter().zip(params.iter_mut()) {
+        let diff = curve.eval(*u) - *point;
+        let d1 = curve.derivative(*u);
+        let d2 = curve.second_derivative(*u);
+
+        let numerator = diff.dot(d1);
+        let denominator = d1.dot(d1) + diff.dot(d2);
+
+        if denominator != 0.0 {
+            let next = *u - numerator / denominator;
+
+            if next.is_finite() {
+                *u = next.clamp(0.0, 1.0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // deterministic noise in [-amplitude, amplitude]
+    fn jitter(points: &mut [Point], amplitude: f32) {
+        let mut state = 0x2545_f491_u32;
+
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            (state as f32 / u32::MAX as f32 * 2.0 - 1.0) * amplitude
+        };
+
+        for point in points {
+            point.x += next();
+            point.y += next();
+        }
+    }
+
+    #[test]
+    fn noisy_line() {
+        let mut points: Vec<Point> = (0..200).map(|i| Point::new(i as f32 * 0.5, 50.0)).collect();
+        jitter(&mut points, 0.3);
+
+        let mut output = Vec::new();
+        fit_curve(&points, 1.0, core::f32::consts::FRAC_PI_4, &mut output);
+        assert!(output.len() <= 4, "{}", output.len());
+    }
+
+    #[test]
+    fn noisy_corner() {
+        let mut points: Vec<Point> = (0..100).map(|i| Point::new(i as f32 * 0.5, 10.0)).collect();
+        points.extend((0..100).map(|i| Point::new(50.0, 10.0 + i as f32 * 0.5)));
+        jitter(&mut points, 0.3);
+
+        let mut output = Vec::new();
+        fit_curve(&points, 1.0, core::f32::consts::FRAC_PI_4, &mut output);
+        assert!(output.len() <= 6, "{}", output.len());
+
+        let corner = Point::new(50.0, 10.0);
+        let has_corner = output.windows(2).any(|w| w[0].c4.distance(corner) < 1.0);
+        assert!(has_corner, "{output:?}");
+    }
+
+    #[test]
+    fn clean_corners() {
+        let points = [
+            Point::new(0.0, 0.0),
+            Point::new(50.0, 0.0),
+            Point::new(50.0, 50.0),
+            Point::new(0.0, 50.0),
+        ];
+
+        let mut output = Vec::new();
+        fit_curve(&points, 0.5, core::f32::consts::FRAC_PI_4, &mut output);
+        assert_eq!(output.len(), 3);
+    }
+}
@@ -0,0 +1,59 @@
+use super::*;
+use alloc::vec::Vec;
+
+/// Converts a composite bezier curve to a polyline
+///
+/// Every point of the path is at most `tolerance` away from the polyline.
+/// The first point is the start of the path and each curve contributes at
+/// least one point: its exact end.
+///
+/// Previous content of `output` is cleared.
+pub fn flatten(path: &[CubicBezier], tolerance: f32, output: &mut Vec<Point>) {
+    output.clear();
+    flatten_with(path, tolerance, |p| output.push(p));
+}
+
+/// Same as [`flatten`], but points are passed to a callback
+pub fn flatten_with<F: FnMut(Point)>(path: &[CubicBezier], tolerance: f32, mut callback: F) {
+    let Some(first) = path.first() else {
+        return;
+    };
+
+    callback(first.c1);
+
+    for curve in path {
+        subdivide(*curve, tolerance, 0, &mut callback);
+    }
+}
+
+fn subdivide<F: FnMut(Point)>(curve: CubicBezier, tolerance: f32, depth: usize, callback: &mut F) {
+    if depth == MAX_DEPTH || is_flat(&curve, tolerance) {
+        callback(curve.c4);
+    } else {
+        // the second half ends exactly where `curve` does
+        let (first_half, second_half) = curve.split_at(0.5);
+        subdivide(first_half, tolerance, depth + 1, callback);
+        subdivide(second_half, tolerance, depth + 1, callback);
+    }
+}
+
+// The curve lies in the convex hull of its control points; if c2 and c3
+// are close to the [c1, c4] segment, so is the whole hull.
+pub(crate) fn is_flat(curve: &CubicBezier, tolerance: f32) -> bool {
+    let max_sq = tolerance * tolerance;
+    let d2 = distance_to_segment_sq(curve.c2, curve.c1, curve.c4);
+    let d3 = distance_to_segment_sq(curve.c3, curve.c1, curve.c4);
+    (d2 <= max_sq) & (d3 <= max_sq)
+}
+
+pub(crate) fn distance_to_segment_sq(p: Point, a: Point, b: Point) -> f32 {
+    let ab = b - a;
+    let len_sq = ab.magnitude_squared();
+
+    let t = match len_sq > 0.0 {
+        true => ((p - a).dot(ab) / len_sq).clamp(0.0, 1.0),
+        false => 0.0,
+    };
+
+    (a + ab * t).distance_squared(p)
+}
@@ -25,6 +25,9 @@ uniform vec4 param_1;
 // mode 4 = tr.x, tr.y, br.x, br.y
 uniform vec4 param_2;
 
+// maps canvas coordinates to texture coordinates
+uniform mat3 inverse_transform;
+
 vec4 rainbow(vec2 point) {
     vec4 rainbow[8];
 
@@ -81,6 +84,11 @@ void main() {
         discard;
     }
 
+    // same coordinate space as the path: the mask shader flips rows,
+    // so canvas row 0 is at the top of the framebuffer, like on screen
+    vec2 canvas_pos = vec2(gl_FragCoord.x, height - gl_FragCoord.y);
+    vec2 point = (inverse_transform * vec3(canvas_pos, 1.0)).xy;
+
     /*__*/ if (mode == 0) {
         // solid color
 
@@ -96,7 +104,7 @@ void main() {
 
         vec2 scaled_size = bmp_size * scale;
 
-        vec2 offset = gl_FragCoord.xy - top_left;
+        vec2 offset = point - top_left;
         if (repeat) offset = mod(offset, scaled_size);
 
         offset = offset / scale;
@@ -112,7 +120,7 @@ void main() {
         vec2 bl = param_1.zw;
         vec2 tr = param_2.xy;
         vec2 br = param_2.zw;
-        vec2 pt = gl_FragCoord.xy;
+        vec2 pt = point;
 
         vec2 e = tr - tl;
         vec2 f = bl - tl;
@@ -156,6 +164,6 @@ void main() {
 
     } else {
         // debug / gradient
-        gl_FragColor = rainbow(gl_FragCoord.xy);
+        gl_FragColor = rainbow(point);
     }
 }
@@ -9,10 +9,14 @@ uniform float height;
 uniform vec2 offset;
 uniform int init;
 
+// 0 = even-odd
+// 1 = non-zero
+uniform int fill_rule;
+
 // [c1x, c1y, c2x, c2y, c3x, c3y, c4x, c4y]
 uniform float input_curve[8];
 
-bool use_segment_for_pip(vec2 p, vec2 s, vec2 e) {
+float winding_delta(vec2 p, vec2 s, vec2 e) {
     vec2 v1 = p - s;
     vec2 v2 = e - s;
     float d = v1.x * v2.y - v1.y * v2.x;
@@ -24,7 +28,7 @@ bool use_segment_for_pip(vec2 p, vec2 s, vec2 e) {
     bool dec = ( b1) && ( b2) && ( b3);
     bool inc = (!b1) && (!b2) && (!b3);
 
-    return (int(inc) - int(dec)) != 0;
+    return float(int(inc) - int(dec));
 }
 
 bool is_curve_straight(vec2 curve[4]) {
@@ -97,8 +101,9 @@ void main() {
 
     if (init == 0) rgba = texture2D(prev_iteration, tex_pos);
 
-    // one bit winding number init
-    bool wind_num = rgba.x != 0.0;
+    // signed winding number init, stored in the green channel
+    float wind_num = 0.0;
+    if (init == 0) wind_num = floor(rgba.y * 255.0 + 0.5) - 128.0;
 
     win_pos.y += offset.y;
     win_pos.x += offset.x;
@@ -125,9 +130,7 @@ void main() {
         if (use_as_is) {
             vec2 c1 = trial_sc[0];
             vec2 c4 = trial_sc[3];
-            if (use_segment_for_pip(win_pos, c1, c4)) {
-                wind_num = !wind_num;
-            }
+            wind_num += winding_delta(win_pos, c1, c4);
 
             // did we complete this curve?
             if (trial == 1.0) {
@@ -144,6 +147,14 @@ void main() {
         }
     }
 
-    float r = wind_num ? 1.0 : 0.0;
-    gl_FragColor = vec4(r, 0.5, 0.5, 1);
+    bool inside;
+    if (fill_rule == 0) {
+        inside = mod(wind_num, 2.0) != 0.0;
+    } else {
+        inside = wind_num != 0.0;
+    }
+
+    float r = inside ? 1.0 : 0.0;
+    float g = (clamp(wind_num, -128.0, 127.0) + 128.0) / 255.0;
+    gl_FragColor = vec4(r, g, 0.5, 1);
 }
@@ -138,11 +138,23 @@ impl super::Canvas for Canvas {
         }
     }
 
-    fn fill_cbc(&mut self, path: &[CubicBezier], texture: &Texture, _ssaa: SsaaConfig) {
+    fn fill_transformed(
+        &mut self,
+        subpaths: &[&[CubicBezier]],
+        transform: &Transform,
+        texture: &Texture,
+        fill_rule: FillRule,
+        _ssaa: SsaaConfig,
+    ) {
+        let Some(inverse) = transform.inverse() else {
+            return;
+        };
+
         let mut shape_aabb = BoundingBox::default();
 
-        for curve in path {
-            shape_aabb = shape_aabb.union(curve.aabb());
+        for curve in subpaths.iter().copied().flatten() {
+            let curve = transform.apply_curve(curve);
+            shape_aabb = shape_aabb.union(curve.tight_bounds());
         }
 
         for y in (0..self.fb_size.y).step_by(256) {
@@ -157,7 +169,7 @@ impl super::Canvas for Canvas {
                     continue;
                 }
 
-                self.tile_pass(path, x, y, texture);
+                self.tile_pass(subpaths, transform, &inverse, x, y, texture, fill_rule);
             }
         }
 
@@ -323,7 +335,17 @@ impl Canvas {
         }
     }
 
-    fn tile_pass(&mut self, path: &[CubicBezier], x: i32, y: i32, texture: &Texture) {
+    #[allow(clippy::too_many_arguments)]
+    fn tile_pass(
+        &mut self,
+        subpaths: &[&[CubicBezier]],
+        transform: &Transform,
+        inverse: &Transform,
+        x: i32,
+        y: i32,
+        texture: &Texture,
+        fill_rule: FillRule,
+    ) {
         debug(&self.gl, "tile_pass");
         unsafe {
             self.gl.bind_framebuffer(FRAMEBUFFER, Some(self.mask_fb));
@@ -344,10 +366,21 @@ impl Canvas {
             self.gl.uniform_2_f32(loc.as_ref(), x as f32, y as f32);
             debug(&self.gl, "offset");
 
+            let fill_rule = match fill_rule {
+                FillRule::EvenOdd => 0,
+                FillRule::NonZero => 1,
+            };
+
+            let loc = self.gl.get_uniform_location(self.mask_program, "fill_rule");
+            self.gl.uniform_1_i32(loc.as_ref(), fill_rule);
+            debug(&self.gl, "fill_rule");
+
             self.gl.viewport(0, 0, 256, 256);
             debug(&self.gl, "viewport");
 
-            for (i, curve) in path.iter().enumerate() {
+            let curves = subpaths.iter().copied().flatten();
+            for (i, curve) in curves.enumerate() {
+                let curve = transform.apply_curve(curve);
                 let coords = [
                     curve.c1.x, curve.c1.y,
                     curve.c2.x, curve.c2.y,
@@ -433,6 +466,17 @@ impl Canvas {
             self.gl.uniform_4_f32_slice(loc.as_ref(), &param_2);
             debug(&self.gl, "[color] param_2");
 
+            // column-major
+            let inverse = [
+                inverse.a, inverse.b, 0.0,
+                inverse.c, inverse.d, 0.0,
+                inverse.e, inverse.f, 1.0,
+            ];
+
+            let loc = self.gl.get_uniform_location(self.color_program, "inverse_transform");
+            self.gl.uniform_matrix_3_f32_slice(loc.as_ref(), false, &inverse);
+            debug(&self.gl, "[color] inverse_transform");
+
             self.gl.active_texture(TEXTURE0);
             self.gl.bind_texture(TEXTURE_2D, Some(self.mask_src));
             self.gl.bind_framebuffer(FRAMEBUFFER, Some(self.render_fb));
@@ -0,0 +1,53 @@
+use super::*;
+use flatten::{flatten_with, distance_to_segment_sq};
+
+// maximum distance between curves and the polylines used for strokes
+const TOLERANCE: f32 = 0.05;
+
+/// Winding number of a composite bezier curve around `point`
+///
+/// This uses the same subdivision and crossing rules as the CPU rasterizer.
+/// Winding numbers of the subpaths of a compound path can be summed.
+pub fn winding_number(path: &[CubicBezier], point: Point) -> i32 {
+    cpu::winding_at(path, point)
+}
+
+/// Tells if `point` is inside the shape which [`Canvas::fill_cbc`] would fill
+///
+/// For pixel centers (e.g. `(x + 0.5, y + 0.5)`), the result is the same as the
+/// CPU rasterizer's without anti-aliasing.
+pub fn contains(path: &[CubicBezier], point: Point, fill_rule: FillRule) -> bool {
+    fill_rule.is_inside(winding_number(path, point))
+}
+
+/// Tells if `point` lies on a stroke of the path, `width` pixels wide
+///
+/// Ends and corners of the stroke are considered round.
+pub fn stroke_contains(path: &[CubicBezier], point: Point, width: f32) -> bool {
+    let max_distance = width * 0.5;
+    let max_sq = max_distance * max_distance;
+    let margin = Vec2::broadcast(max_distance);
+
+    for curve in path {
+        let aabb = curve.aabb();
+        let grown = BoundingBox::new(aabb.min - margin, aabb.max + margin);
+
+        if !grown.contains(point) {
+            continue;
+        }
+
+        let mut start = curve.c1;
+        let mut hit = false;
+
+        flatten_with(core::slice::from_ref(curve), TOLERANCE, |end| {
+            hit |= distance_to_segment_sq(point, start, end) <= max_sq;
+            start = end;
+        });
+
+        if hit {
+            return true;
+        }
+    }
+
+    false
+}
@@ -0,0 +1,460 @@
+use super::*;
+use alloc::vec::Vec;
+use flatten::{flatten_with, distance_to_segment_sq};
+
+// curves closer than this to a uniformly parameterized
+// line segment are treated as one
+const FLATNESS: f32 = 1e-3;
+
+// bounds the recursion on degenerate or overlapping curves
+const MAX_DEPTH: usize = 32;
+
+// intersections closer than this (in `t` units) are merged
+const T_EPSILON: f32 = 1e-4;
+
+// curves closer than this around an intersection are considered overlapping
+const OVERLAP: f32 = 0.01;
+
+/// A point where two curves (or a curve and a line) cross
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Intersection {
+    /// Parameter on the first curve
+    pub t1: f32,
+    /// Parameter on the second curve or on the line
+    pub t2: f32,
+    /// Location of the intersection, evaluated on the first curve
+    pub point: Point,
+}
+
+/// Finds the points where two curves cross or touch
+///
+/// Results are sorted by `t1`. Parts where both curves overlap don't
+/// produce intersections, except where an overlap ends at the end of
+/// either curve. Intersections are accurate to about 0.001 pixel.
+///
+/// Previous content of `output` is cleared.
+pub fn intersect_curves(a: &CubicBezier, b: &CubicBezier, output: &mut Vec<Intersection>) {
+    output.clear();
+    intersect_parts(Part::new(*a), Part::new(*b), output);
+}
+
+/// Finds the points where a curve crosses the segment going from `from` to `to`
+///
+/// `t2` is the position on the segment, from 0 (at `from`) to 1 (at `to`).
+/// Results are sorted by `t1`.
+///
+/// Previous content of `output` is cleared.
+pub fn intersect_segment(curve: &CubicBezier, from: Point, to: Point, output: &mut Vec<Intersection>) {
+    output.clear();
+
+    if from != to {
+        let segment = Part::segment(from, to);
+        intersect_parts(Part::new(*curve), segment, output);
+    }
+}
+
+/// Finds the points where a curve crosses a ray
+///
+/// `t2` is the position on the ray: the intersection point is at
+/// `origin + direction * t2`. Results are sorted by `t1`.
+///
+/// Previous content of `output` is cleared.
+pub fn intersect_ray(curve: &CubicBezier, origin: Point, direction: Vec2<f32>, output: &mut Vec<Intersection>) {
+    output.clear();
+
+    let len_sq = direction.magnitude_squared();
+    if len_sq == 0.0 {
+        return;
+    }
+
+    // the ray is cut where it leaves the curve's bounding box
+    let aabb = curve.aabb();
+    let corners = [aabb.min, aabb.max, Point::new(aabb.min.x, aabb.max.y), Point::new(aabb.max.x, aabb.min.y)];
+    let reach = corners.iter().map(|c| (*c - origin).dot(direction) / len_sq).fold(0.0, f32::max);
+
+    if reach > 0.0 {
+        let segment = Part::segment(origin, origin + direction * reach);
+        intersect_parts(Part::new(*curve), segment, output);
+
+        for found in output.iter_mut() {
+            found.t2 *= reach;
+        }
+    }
+}
+
+/// Finds the point where a curve crosses itself, if it does
+///
+/// A cubic curve has at most one self-intersection; `t1` is smaller than `t2`.
+pub fn self_intersection(curve: &CubicBezier) -> Option<Intersection> {
+    // power basis: B(t) = a t³ + b t² + c t + d
+    let CubicBezier { c1, c2, c3, c4 } = *curve;
+    let a = (c2 - c3) * 3.0 + c4 - c1;
+    let b = (c1 - c2 * 2.0 + c3) * 3.0;
+    let c = (c2 - c1) * 3.0;
+
+    // B(s) = B(t) with s != t, divided by (s - t):
+    // a (σ² - π) + b σ + c = 0, where σ = s + t and π = s t
+    let denom = cross(b, a);
+
+    if denom == 0.0 {
+        return None;
+    }
+
+    let sigma = cross(a, c) / denom;
+
+    let pi = match a.x.abs() > a.y.abs() {
+        true => sigma * sigma + (b.x * sigma + c.x) / a.x,
+        false => sigma * sigma + (b.y * sigma + c.y) / a.y,
+    };
+
+    // s and t are the roots of z² - σ z + π
+    let discriminant = sigma * sigma - 4.0 * pi;
+
+    // NaN roots are rejected below
+    if discriminant <= 0.0 {
+        return None;
+    }
+
+    let root = discriminant.sqrt();
+    let t1 = (sigma - root) * 0.5;
+    let t2 = (sigma + root) * 0.5;
+
+    let range = 0.0..=1.0;
+
+    match range.contains(&t1) && range.contains(&t2) {
+        true => Some(Intersection {
+            t1,
+            t2,
+            point: curve.eval(t1),
+        }),
+        false => None,
+    }
+}
+
+fn intersect_parts(a: Part, b: Part, output: &mut Vec<Intersection>) {
+    recurse(a, b, 0, output);
+
+    output.sort_by(|x, y| x.t1.total_cmp(&y.t1));
+    dedup(output);
+
+    output.retain(|found| !overlapping(&a.curve, &b.curve, found.t1, found.t2));
+
+    for found in output.iter_mut() {
+        found.point = a.curve.eval(found.t1);
+    }
+}
+
+// a piece of curve, remembering where it lies on the original one
+#[derive(Copy, Clone)]
+struct Part {
+    curve: CubicBezier,
+    t0: f32,
+    t1: f32,
+}
+
+impl Part {
+    fn new(curve: CubicBezier) -> Self {
+        Self {
+            curve,
+            t0: 0.0,
+            t1: 1.0,
+        }
+    }
+
+    // unlike `CubicBezier::segment`, this one is uniformly parameterized
+    fn segment(from: Point, to: Point) -> Self {
+        let third = (to - from) / 3.0;
+
+        Self::new(CubicBezier {
+            c1: from,
+            c2: from + third,
+            c3: to - third,
+            c4: to,
+        })
+    }
+
+    fn split(&self) -> (Self, Self) {
+        let (first, second) = self.curve.split_at(0.5);
+        let mid = (self.t0 + self.t1) * 0.5;
+
+        let first = Self {
+            curve: first,
+            t0: self.t0,
+            t1: mid,
+        };
+
+        let second = Self {
+            curve: second,
+            t0: mid,
+            t1: self.t1,
+        };
+
+        (first, second)
+    }
+
+    // Being flat isn't enough: parameters found on the chord must also
+    // match the curve's, which requires evenly spaced control points.
+    fn is_flat(&self) -> bool {
+        let CubicBezier { c1, c2, c3, c4 } = self.curve;
+        let max_sq = FLATNESS * FLATNESS;
+        let third = (c4 - c1) / 3.0;
+        let d2 = c2.distance_squared(c1 + third);
+        let d3 = c3.distance_squared(c4 - third);
+        (d2 <= max_sq) & (d3 <= max_sq)
+    }
+
+    fn global_t(&self, local: f32) -> f32 {
+        self.t0 + (self.t1 - self.t0) * local
+    }
+}
+
+fn recurse(a: Part, b: Part, depth: usize, output: &mut Vec<Intersection>) {
+    if !a.curve.aabb().overlaps_with(b.curve.aabb()) {
+        return;
+    }
+
+    let a_flat = a.is_flat();
+    let b_flat = b.is_flat();
+
+    if (a_flat & b_flat) || depth == MAX_DEPTH {
+        let a_seg = (a.curve.c1, a.curve.c4);
+        let b_seg = (b.curve.c1, b.curve.c4);
+
+        if let Some((s, u)) = segment_segment(a_seg, b_seg) {
+            output.push(Intersection {
+                t1: a.global_t(s),
+                t2: b.global_t(u),
+                // computed once all intersections are known
+                point: Point::zero(),
+            });
+        }
+
+        return;
+    }
+
+    match (a_flat, b_flat) {
+        (false, true) => {
+            let (a1, a2) = a.split();
+            recurse(a1, b, depth + 1, output);
+            recurse(a2, b, depth + 1, output);
+        },
+        (true, false) => {
+            let (b1, b2) = b.split();
+            recurse(a, b1, depth + 1, output);
+            recurse(a, b2, depth + 1, output);
+        },
+        _ => {
+            let (a1, a2) = a.split();
+            let (b1, b2) = b.split();
+            recurse(a1, b1, depth + 1, output);
+            recurse(a1, b2, depth + 1, output);
+            recurse(a2, b1, depth + 1, output);
+            recurse(a2, b2, depth + 1, output);
+        },
+    }
+}
+
+// Intersection of two line segments, as parameters along each one.
+// Parallel (or nearly parallel) segments never intersect.
+fn segment_segment(a: (Point, Point), b: (Point, Point)) -> Option<(f32, f32)> {
+    // tolerance on parameters, so that segments
+    // sharing an end point do intersect
+    const MARGIN: f32 = 1e-5;
+
+    let r = a.1 - a.0;
+    let w = b.1 - b.0;
+    let qp = b.0 - a.0;
+
+    let denom = cross(r, w);
+
+    // also rejects nearly parallel segments, which
+    // are found all along overlapping curves
+    let min_denom = 1e-6 * (r.magnitude_squared() * w.magnitude_squared()).sqrt();
+
+    if denom.abs() <= min_denom {
+        return None;
+    }
+
+    let s = cross(qp, w) / denom;
+    let u = cross(qp, r) / denom;
+
+    let range = -MARGIN..=(1.0 + MARGIN);
+
+    match range.contains(&s) && range.contains(&u) {
+        true => Some((s.clamp(0.0, 1.0), u.clamp(0.0, 1.0))),
+        false => None,
+    }
+}
+
+// Along overlapping parts, pieces of one curve touch pieces
+// of the other at their ends: these aren't real intersections.
+// Where an overlap ends with the end of a curve, the other curve
+// is checked, so that the end of the overlap is kept.
+fn overlapping(a: &CubicBezier, b: &CubicBezier, t1: f32, t2: f32) -> bool {
+    if cross(a.tangent(t1), b.tangent(t2)).abs() > 1e-3 {
+        return false;
+    }
+
+    let interior = |t: f32| t > T_EPSILON && t < 1.0 - T_EPSILON;
+    (interior(t1) && follows(a, b, t1)) || (interior(t2) && follows(b, a, t2))
+}
+
+// true if `a` stays on `b` on both sides of `t`
+fn follows(a: &CubicBezier, b: &CubicBezier, t: f32) -> bool {
+    const STEP: f32 = 0.01;
+
+    [t - STEP, t + STEP].into_iter().all(|t| {
+        let point = a.eval(t.clamp(0.0, 1.0));
+        let mut start = b.c1;
+        let mut close = false;
+
+        flatten_with(core::slice::from_ref(b), OVERLAP * 0.5, |end| {
+            close |= distance_to_segment_sq(point, start, end) <= OVERLAP * OVERLAP;
+            start = end;
+        });
+
+        close
+    })
+}
+
+#[inline(always)]
+fn cross(a: Vec2<f32>, b: Vec2<f32>) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+// removes intersections found twice, e.g. at the junction of two parts
+fn dedup(found: &mut Vec<Intersection>) {
+    let mut kept = 0;
+
+    for i in 0..found.len() {
+        let candidate = found[i];
+        let duplicate = found[..kept].iter().any(|other| {
+            (other.t1 - candidate.t1).abs() < T_EPSILON && (other.t2 - candidate.t2).abs() < T_EPSILON
+        });
+
+        if !duplicate {
+            found[kept] = candidate;
+            kept += 1;
+        }
+    }
+
+    found.truncate(kept);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crossing_lines() {
+        let a = CubicBezier::segment(Point::new(0.0, 0.0), Point::new(10.0, 10.0));
+        let b = CubicBezier::segment(Point::new(0.0, 10.0), Point::new(10.0, 0.0));
+        let mut output = Vec::new();
+
+        intersect_curves(&a, &b, &mut output);
+        assert_eq!(output.len(), 1, "{output:?}");
+        assert!(output[0].point.distance(Point::new(5.0, 5.0)) < 1e-3);
+        assert!((output[0].t1 - 0.5).abs() < 1e-3 && (output[0].t2 - 0.5).abs() < 1e-3);
+
+        intersect_segment(&a, Point::new(0.0, 4.0), Point::new(10.0, 4.0), &mut output);
+        assert_eq!(output.len(), 1, "{output:?}");
+        assert!(output[0].point.distance(Point::new(4.0, 4.0)) < 1e-3);
+        assert!((output[0].t2 - 0.4).abs() < 1e-3);
+
+        intersect_segment(&a, Point::new(0.0, 4.0), Point::new(2.0, 4.0), &mut output);
+        assert!(output.is_empty());
+    }
+
+    #[cfg(feature = "shapes")]
+    #[test]
+    fn ray_against_circle() {
+        let center = Point::new(50.0, 50.0);
+        let mut circle = Vec::new();
+        shapes::arc(center, Vec2::broadcast(30.0), 0.0, 0.0, core::f32::consts::TAU, 0.01, &mut circle);
+
+        let cast = |origin: Point, direction: Vec2<f32>| {
+            let mut hits = Vec::new();
+            let mut output = Vec::new();
+
+            for curve in &circle {
+                intersect_ray(curve, origin, direction, &mut output);
+                hits.extend_from_slice(&output);
+            }
+
+            hits
+        };
+
+        // from the center: one hit, at the radius
+        let direction = Vec2::new(3.0, 1.0);
+        let hits = cast(center, direction);
+        assert_eq!(hits.len(), 1, "{hits:?}");
+        assert!((hits[0].point.distance(center) - 30.0).abs() < 0.02);
+        assert!((hits[0].t2 - 30.0 / direction.magnitude()).abs() < 0.02);
+
+        // from outside: in and out
+        let mut hits = cast(Point::new(0.0, 45.0), Vec2::new(1.0, 0.0));
+        hits.sort_by(|a, b| a.t2.total_cmp(&b.t2));
+        let half_chord = (30.0f32 * 30.0 - 5.0 * 5.0).sqrt();
+        assert_eq!(hits.len(), 2, "{hits:?}");
+        assert!((hits[0].t2 - (50.0 - half_chord)).abs() < 0.02);
+        assert!((hits[1].t2 - (50.0 + half_chord)).abs() < 0.02);
+
+        // pointing away
+        assert!(cast(Point::new(0.0, 45.0), Vec2::new(-1.0, 0.0)).is_empty());
+    }
+
+    #[test]
+    fn tangent_and_coincident_curves() {
+        // the top of the arch is at (10, -15)
+        let arch = CubicBezier {
+            c1: Point::new(0.0, 0.0),
+            c2: Point::new(0.0, -20.0),
+            c3: Point::new(20.0, -20.0),
+            c4: Point::new(20.0, 0.0),
+        };
+
+        let mut output = Vec::new();
+        intersect_segment(&arch, Point::new(-10.0, -15.0), Point::new(30.0, -15.0), &mut output);
+        assert!(output.len() <= 1, "{output:?}");
+
+        for found in &output {
+            assert!(found.point.distance(Point::new(10.0, -15.0)) < 0.1, "{found:?}");
+        }
+
+        // overlapping curves don't produce a flood of results
+        intersect_curves(&arch, &arch, &mut output);
+        assert!(output.len() <= 2, "{output:?}");
+
+        // where the overlap ends is kept, once
+        let (first_half, _) = arch.split_at(0.5);
+        intersect_curves(&arch, &first_half, &mut output);
+        assert!(output.len() <= 2, "{output:?}");
+
+        let ends = output.iter().filter(|found| (found.t1 - 0.5).abs() < 0.01).count();
+        assert_eq!(ends, 1, "{output:?}");
+        assert!(output.iter().all(|found| found.t1 < 1e-3 || (found.t1 - 0.5).abs() < 1e-3), "{output:?}");
+    }
+
+    #[test]
+    fn self_intersections() {
+        let looped = CubicBezier {
+            c1: Point::new(0.0, 0.0),
+            c2: Point::new(120.0, 100.0),
+            c3: Point::new(-20.0, 100.0),
+            c4: Point::new(100.0, 0.0),
+        };
+
+        let found = self_intersection(&looped).unwrap();
+        assert!(found.t1 < found.t2);
+        assert!(looped.eval(found.t1).distance(looped.eval(found.t2)) < 1e-2);
+
+        let arch = CubicBezier {
+            c1: Point::new(0.0, 0.0),
+            c2: Point::new(0.0, -20.0),
+            c3: Point::new(20.0, -20.0),
+            c4: Point::new(20.0, 0.0),
+        };
+
+        assert!(self_intersection(&arch).is_none());
+    }
+}
@@ -19,11 +19,47 @@ use vek::num_traits::Float;
 mod contour;
 
 #[cfg(any(doc, feature = "contour"))]
-pub use contour::contour;
+pub use contour::{contour, variable_contour, StrokeStyle, LineJoin, LineCap};
+
+#[cfg(any(doc, feature = "contour"))]
+pub use dash::dash_contour;
 
 #[cfg(any(doc, feature = "shapes"))]
 pub mod shapes;
 
+#[cfg(any(doc, feature = "boolean"))]
+pub mod boolean;
+
+#[cfg(any(doc, feature = "svg"))]
+pub mod svg;
+
+pub mod analysis;
+
+mod curve;
+mod dash;
+mod fit;
+mod flatten;
+mod hit;
+mod intersect;
+mod measure;
+mod morph;
+mod path;
+mod perspective;
+mod segment;
+mod transform;
+
+pub use dash::dash;
+pub use fit::fit_curve;
+pub use flatten::{flatten, flatten_with};
+pub use hit::{contains, stroke_contains, winding_number};
+pub use intersect::{Intersection, intersect_curves, intersect_segment, intersect_ray, self_intersection};
+pub use measure::{PathMeasure, trim};
+pub use morph::{Morph, interpolate};
+pub use path::PathBuilder;
+pub use perspective::Perspective;
+pub use segment::{Segment, CONIC_TOLERANCE};
+pub use transform::Transform;
+
 /// Implementations of [`Canvas`] using only the CPU
 pub mod cpu;
 
@@ -87,10 +123,13 @@ pub type Color = rgb::RGBA<u8>;
 /// Pixel or Subpixel coordinates
 pub type Point = Vec2<f32>;
 
-#[derive(Copy, Clone)]
-struct BoundingBox {
-    min: Point,
-    max: Point,
+/// Axis-aligned rectangle
+///
+/// The default value is an empty box which can be grown with [`BoundingBox::union`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point,
 }
 
 impl Default for BoundingBox {
@@ -103,13 +142,45 @@ impl Default for BoundingBox {
 }
 
 impl BoundingBox {
-    fn overlaps_with(&self, other: BoundingBox) -> bool {
+    pub fn new(min: Point, max: Point) -> Self {
+        Self { min, max }
+    }
+
+    /// Smallest box containing all curves of a path
+    ///
+    /// This is based on [`CubicBezier::tight_bounds`].
+    pub fn of_path(path: &[CubicBezier]) -> Self {
+        path.iter().fold(Self::default(), |aabb, curve| aabb.union(curve.tight_bounds()))
+    }
+
+    /// Returns true if this box contains no point
+    pub fn is_empty(&self) -> bool {
+        !((self.min.x <= self.max.x) & (self.min.y <= self.max.y))
+    }
+
+    /// Width and height of the box (zero if empty)
+    pub fn size(&self) -> Vec2<f32> {
+        (self.max - self.min).map(|f| f.max(0.0))
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        let x_inside = (self.min.x <= p.x) & (p.x <= self.max.x);
+        let y_inside = (self.min.y <= p.y) & (p.y <= self.max.y);
+        x_inside & y_inside
+    }
+
+    pub fn overlaps_with(&self, other: BoundingBox) -> bool {
         let x_overlap = (self.min.x <= other.max.x) & (self.max.x >= other.min.x);
         let y_overlap = (self.min.y <= other.max.y) & (self.max.y >= other.min.y);
         x_overlap & y_overlap
     }
 
-    fn union(&self, other: BoundingBox) -> Self {
+    /// Grows the box so that it contains `p`
+    pub fn include(&self, p: Point) -> Self {
+        self.union(Self::new(p, p))
+    }
+
+    pub fn union(&self, other: BoundingBox) -> Self {
         let min_x = self.min.x.min(other.min.x);
         let min_y = self.min.y.min(other.min.y);
         let max_x = self.max.x.max(other.max.x);
@@ -132,6 +203,26 @@ pub struct CubicBezier {
     pub c4: Point,
 }
 
+/// Decides which regions enclosed by a path are filled
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum FillRule {
+    /// Points are inside if a ray cast from them crosses the path an odd number of times
+    #[default]
+    EvenOdd,
+    /// Points are inside if the path winds around them at least once, in any direction
+    NonZero,
+}
+
+impl FillRule {
+    /// Tells if a point with this winding number is inside the shape
+    pub fn is_inside(&self, winding: i32) -> bool {
+        match self {
+            Self::EvenOdd => (winding & 1) != 0,
+            Self::NonZero => winding != 0,
+        }
+    }
+}
+
 /// The trait that rendering backends must implement
 pub trait Canvas {
     fn framebuffer_size(&self) -> Vec2<usize>;
@@ -148,7 +239,41 @@ pub trait Canvas {
     /// In other words: in the `path` slice, a curve at index N must end where the N+1 curve starts;
     /// additionally, the last curve must end where the first one starts.
     ///
-    fn fill_cbc(&mut self, cbc: &[CubicBezier], texture: &Texture, ssaa: SsaaConfig);
+    /// Self-overlapping regions are filled according to `fill_rule`.
+    fn fill_cbc(&mut self, cbc: &[CubicBezier], texture: &Texture, fill_rule: FillRule, ssaa: SsaaConfig) {
+        self.fill_compound(&[cbc], texture, fill_rule, ssaa)
+    }
+
+    /// Fills a shape delimited by multiple closed paths (subpaths)
+    ///
+    /// Each subpath must satisfy the requirements of [`Canvas::fill_cbc`].
+    /// All subpaths are rasterized together, so holes and shared edges are
+    /// antialiased without seams. Holes are obtained either by reversing the
+    /// direction of inner subpaths or by using [`FillRule::EvenOdd`].
+    fn fill_compound(&mut self, subpaths: &[&[CubicBezier]], texture: &Texture, fill_rule: FillRule, ssaa: SsaaConfig) {
+        self.fill_transformed(subpaths, &Transform::IDENTITY, texture, fill_rule, ssaa)
+    }
+
+    /// Same as [`Canvas::fill_compound`], with `transform` applied to the subpaths
+    ///
+    /// Texture coordinates follow the same transform, so a bitmap stays attached
+    /// to the shape it fills. Nothing is drawn if the transform isn't invertible.
+    fn fill_transformed(
+        &mut self,
+        subpaths: &[&[CubicBezier]],
+        transform: &Transform,
+        texture: &Texture,
+        fill_rule: FillRule,
+        ssaa: SsaaConfig,
+    );
+
+    /// Same as [`Canvas::fill_compound`], for subpaths made of [`Segment`]s
+    ///
+    /// The default implementation converts segments to cubic curves, with
+    /// [`CONIC_TOLERANCE`] for conics. Backends can process them natively.
+    fn fill_segments(&mut self, subpaths: &[&[Segment]], texture: &Texture, fill_rule: FillRule, ssaa: SsaaConfig) {
+        segment::fill_as_cubics(self, subpaths, texture, fill_rule, ssaa)
+    }
 }
 
 #[inline(always)]
@@ -159,8 +284,32 @@ fn travel(a: Point, b: Point, t: f32) -> Point {
     }
 }
 
+// curves are never split more than this many times, which bounds
+// the work done on degenerate input (NaN, zero tolerance)
+const MAX_DEPTH: usize = 16;
+
+/// True if the path ends where it starts; empty paths aren't closed
+pub(crate) fn is_closed(path: &[CubicBezier]) -> bool {
+    match (path.first(), path.last()) {
+        (Some(first), Some(last)) => first.c1 == last.c4,
+        _ => false,
+    }
+}
+
 impl CubicBezier {
-    fn split(self, t: f32) -> (Self, Self) {
+    fn segment(start: Point, end: Point) -> Self {
+        Self {
+            c1: start,
+            c2: start,
+            c3: end,
+            c4: end,
+        }
+    }
+
+    /// Splits the curve in two at `t`, using De Casteljau's algorithm
+    ///
+    /// The first curve ends exactly where the second one starts.
+    pub fn split_at(self, t: f32) -> (Self, Self) {
         let side1 = travel(self.c1, self.c2, t);
         let side2 = travel(self.c2, self.c3, t);
         let side3 = travel(self.c3, self.c4, t);
@@ -195,26 +344,6 @@ impl CubicBezier {
             max: Point::new(max_x, max_y),
         }
     }
-
-    fn split_4(&self) -> [Self; 4] {
-        let (ab, cd) = self.split(0.5);
-        let (a, b) = ab.split(0.5);
-        let (c, d) = cd.split(0.5);
-        [a, b, c, d]
-    }
-
-    fn overlaps(&self, tile: BoundingBox) -> bool {
-        if self.aabb().overlaps_with(tile) {
-            let [a, b, c, d] = self.split_4();
-            let (aabb_1, aabb_2) = (a.aabb(), b.aabb());
-            let (aabb_3, aabb_4) = (c.aabb(), d.aabb());
-
-            aabb_1.overlaps_with(tile) || aabb_2.overlaps_with(tile) ||
-            aabb_3.overlaps_with(tile) || aabb_4.overlaps_with(tile)
-        } else {
-            false
-        }
-    }
 }
 
 fn min_max(input: [f32; 4]) -> (f32, f32) {
@@ -0,0 +1,315 @@
+use super::*;
+use alloc::vec::Vec;
+
+// accuracy of the measurements made by `trim`
+const TRIM_TOLERANCE: f32 = 0.01;
+
+/// Arc length measurements along a composite bezier curve
+///
+/// Curves are approximated with polylines which are at most `tolerance` away
+/// from them; smaller tolerances give more accurate lengths and positions.
+///
+/// Distances are clamped to the `[0, length]` range.
+///
+/// ```rust
+/// use wizdraw::{PathMeasure, PathBuilder, Point};
+///
+/// let mut builder = PathBuilder::new();
+/// builder.move_to(Point::new(0.0, 0.0));
+/// builder.line_to(Point::new(30.0, 40.0));
+/// let path = builder.build_open();
+///
+/// let measure = PathMeasure::new(&path[0], 0.01);
+/// assert!((measure.length() - 50.0).abs() < 0.01);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PathMeasure<'a> {
+    path: &'a [CubicBezier],
+    // (t, distance from the start of the path)
+    samples: Vec<(f32, f32)>,
+    // index of the first sample of each curve
+    first_samples: Vec<usize>,
+    // distance from the start of the path to the end of each curve
+    ends: Vec<f32>,
+}
+
+impl<'a> PathMeasure<'a> {
+    /// Measures `path`, sampling its curves ahead of time
+    ///
+    /// `tolerance` is the maximum distance between the curves and the
+    /// polylines used to measure them, as described on the type.
+    pub fn new(path: &'a [CubicBezier], tolerance: f32) -> Self {
+        let mut measure = Self {
+            path,
+            samples: Vec::new(),
+            first_samples: Vec::with_capacity(path.len()),
+            ends: Vec::with_capacity(path.len()),
+        };
+
+        let mut distance = 0.0;
+
+        for curve in path {
+            measure.first_samples.push(measure.samples.len());
+            measure.samples.push((0.0, distance));
+            measure.subdivide(*curve, 0.0, 1.0, tolerance, 0, &mut distance);
+            measure.ends.push(distance);
+        }
+
+        measure
+    }
+
+    fn subdivide(&mut self, curve: CubicBezier, t0: f32, t1: f32, tolerance: f32, depth: usize, distance: &mut f32) {
+        if depth == MAX_DEPTH || is_uniform(&curve, tolerance) {
+            *distance += curve.c1.distance(curve.c4);
+            self.samples.push((t1, *distance));
+        } else {
+            let mid = (t0 + t1) * 0.5;
+            let (first_half, second_half) = curve.split_at(0.5);
+            self.subdivide(first_half, t0, mid, tolerance, depth + 1, distance);
+            self.subdivide(second_half, mid, t1, tolerance, depth + 1, distance);
+        }
+    }
+
+    /// Total length of the path
+    pub fn length(&self) -> f32 {
+        self.ends.last().copied().unwrap_or(0.0)
+    }
+
+    /// Length of the curve at `index` in the path
+    pub fn segment_length(&self, index: usize) -> f32 {
+        self.ends[index] - self.segment_start(index)
+    }
+
+    /// Distance from the start of the path to the start of the curve at `index`
+    pub fn segment_start(&self, index: usize) -> f32 {
+        match index {
+            0 => 0.0,
+            i => self.ends[i - 1],
+        }
+    }
+
+    /// Finds which curve is at `distance`, and where on that curve
+    ///
+    /// Returns the index of the curve and the matching `t` parameter, or
+    /// `None` if the path is empty.
+    pub fn locate(&self, distance: f32) -> Option<(usize, f32)> {
+        let last = self.path.len().checked_sub(1)?;
+        let distance = distance.clamp(0.0, self.length());
+
+        let index = self.ends.partition_point(|end| *end < distance).min(last);
+
+        let samples = self.curve_samples(index);
+        let i = samples.partition_point(|(_, d)| *d < distance).clamp(1, samples.len() - 1);
+        let (t0, d0) = samples[i - 1];
+        let (t1, d1) = samples[i];
+
+        let t = match d1 > d0 {
+            true => t0 + (t1 - t0) * ((distance - d0) / (d1 - d0)),
+            false => t0,
+        };
+
+        Some((index, t.clamp(0.0, 1.0)))
+    }
+
+    /// Distance from the start of the path to the point at `t` on the curve at `index`
+    ///
+    /// This is the inverse of [`PathMeasure::locate`].
+    pub fn distance_at(&self, index: usize, t: f32) -> f32 {
+        let samples = self.curve_samples(index);
+        let i = samples.partition_point(|(s, _)| *s < t).clamp(1, samples.len() - 1);
+        let (t0, d0) = samples[i - 1];
+        let (t1, d1) = samples[i];
+
+        match t1 > t0 {
+            true => (d0 + (d1 - d0) * ((t - t0) / (t1 - t0))).clamp(d0, d1),
+            false => d0,
+        }
+    }
+
+    // (t, distance) samples of the curve at `index`
+    fn curve_samples(&self, index: usize) -> &[(f32, f32)] {
+        let first = self.first_samples[index];
+
+        match self.first_samples.get(index + 1) {
+            Some(next) => &self.samples[first..*next],
+            None => &self.samples[first..],
+        }
+    }
+
+    /// Point at `distance` from the start of the path
+    pub fn point_at(&self, distance: f32) -> Option<Point> {
+        let (index, t) = self.locate(distance)?;
+        Some(self.path[index].eval(t))
+    }
+
+    /// Unit tangent vector at `distance` from the start of the path
+    pub fn tangent_at(&self, distance: f32) -> Option<Vec2<f32>> {
+        let (index, t) = self.locate(distance)?;
+        Some(self.path[index].tangent(t))
+    }
+
+    /// Direction of the path at `distance` from its start, in radians
+    ///
+    /// Angles are measured from the X axis, towards the Y axis.
+    pub fn angle_at(&self, distance: f32) -> Option<f32> {
+        let tangent = self.tangent_at(distance)?;
+        Some(tangent.y.atan2(tangent.x))
+    }
+
+    /// Appends the part of the path between two distances to `output`
+    ///
+    /// The curves at both ends are split precisely; curves in between are
+    /// copied as is, so the result is a valid composite bezier curve. Nothing
+    /// is appended if `from` isn't smaller than `to`.
+    pub fn extract(&self, from: f32, to: f32, output: &mut Vec<CubicBezier>) {
+        if from >= to {
+            return;
+        }
+
+        let (Some((i0, t0)), Some((i1, t1))) = (self.locate(from), self.locate(to)) else {
+            return;
+        };
+
+        if i0 == i1 {
+            output.push(exact_subsegment(&self.path[i0], t0, t1));
+            return;
+        }
+
+        // skip the end of a curve, if that's where `from` is
+        if t0 < 1.0 {
+            output.push(exact_subsegment(&self.path[i0], t0, 1.0));
+        }
+
+        output.extend_from_slice(&self.path[(i0 + 1)..i1]);
+        output.push(exact_subsegment(&self.path[i1], 0.0, t1));
+    }
+}
+
+// Being flat isn't enough: distances are mapped linearly to `t` between
+// samples, which requires evenly spaced control points. The curve is then
+// at most `tolerance` away from the chord traversed at constant speed.
+fn is_uniform(curve: &CubicBezier, tolerance: f32) -> bool {
+    let CubicBezier { c1, c2, c3, c4 } = *curve;
+    let max_sq = tolerance * tolerance;
+    let third = (c4 - c1) / 3.0;
+    let d2 = c2.distance_squared(c1 + third);
+    let d3 = c3.distance_squared(c4 - third);
+    (d2 <= max_sq) & (d3 <= max_sq)
+}
+
+// keeps end points bit-exact, so that pieces stay connected to their neighbours
+fn exact_subsegment(curve: &CubicBezier, t0: f32, t1: f32) -> CubicBezier {
+    let mut piece = curve.subsegment(t0, t1);
+
+    if t0 == 0.0 {
+        piece.c1 = curve.c1;
+    }
+
+    if t1 == 1.0 {
+        piece.c4 = curve.c4;
+    }
+
+    piece
+}
+
+/// Cuts a path between two fractions of its length
+///
+/// `start` and `end` range from 0 (the start of the path) to 1 (its end).
+/// On closed paths, `start` can be greater than `end`: the result then goes
+/// through the start of the path. On open paths, the result is empty in that
+/// case.
+///
+/// The result is a valid composite bezier curve, which can be passed to `contour`.
+pub fn trim(path: &[CubicBezier], start: f32, end: f32) -> Vec<CubicBezier> {
+    let measure = PathMeasure::new(path, TRIM_TOLERANCE);
+    let length = measure.length();
+
+    let start = start.clamp(0.0, 1.0) * length;
+    let end = end.clamp(0.0, 1.0) * length;
+
+    let mut output = Vec::new();
+
+    if start <= end {
+        measure.extract(start, end, &mut output);
+    } else if is_closed(path) {
+        measure.extract(start, length, &mut output);
+        measure.extract(0.0, end, &mut output);
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(to: Point) -> Vec<CubicBezier> {
+        let mut builder = PathBuilder::new();
+        builder.move_to(Point::zero());
+        builder.line_to(to);
+        builder.build_open().remove(0)
+    }
+
+    #[test]
+    fn point_at_on_lines() {
+        let path = line(Point::new(10.0, 0.0));
+        let measure = PathMeasure::new(&path, 0.01);
+
+        for distance in [0.0, 1.0, 3.0, 5.0, 7.5, 10.0] {
+            let point = measure.point_at(distance).unwrap();
+            assert!((point.x - distance).abs() < 0.02, "{distance}: {point:?}");
+        }
+
+        // evenly spaced control points, but not at thirds
+        let curve = CubicBezier {
+            c1: Point::zero(),
+            c2: Point::new(30.0, 0.0),
+            c3: Point::new(70.0, 0.0),
+            c4: Point::new(100.0, 0.0),
+        };
+
+        let measure = PathMeasure::new(core::slice::from_ref(&curve), 0.01);
+        let point = measure.point_at(30.0).unwrap();
+        assert!((point.x - 30.0).abs() < 0.02, "{point:?}");
+    }
+
+    #[test]
+    fn distance_at_on_lines() {
+        let path = line(Point::new(10.0, 0.0));
+        let measure = PathMeasure::new(&path, 0.01);
+
+        for t in [0.0, 0.1, 0.25, 0.5, 0.8, 1.0] {
+            let distance = measure.distance_at(0, t);
+            let expected = path[0].eval(t).x;
+            assert!((distance - expected).abs() < 0.02, "{t}: {distance}");
+        }
+    }
+
+    #[test]
+    fn trim_lines() {
+        let path = line(Point::new(10.0, 0.0));
+        let trimmed = trim(&path, 0.25, 0.75);
+        assert!((trimmed[0].c1.x - 2.5).abs() < 0.02, "{trimmed:?}");
+        assert!((trimmed.last().unwrap().c4.x - 7.5).abs() < 0.02, "{trimmed:?}");
+    }
+
+    #[test]
+    fn trim_wraps_around() {
+        let mut builder = PathBuilder::new();
+        builder.move_to(Point::zero());
+        builder.line_to(Point::new(10.0, 0.0));
+        builder.line_to(Point::new(10.0, 10.0));
+        builder.line_to(Point::new(0.0, 10.0));
+        builder.close();
+
+        let path = builder.build().remove(0);
+        let trimmed = trim(&path, 0.9, 0.1);
+
+        let length = PathMeasure::new(&trimmed, 0.01).length();
+        assert!((length - 8.0).abs() < 0.02, "{length}");
+
+        // 36 -> 40 on the left side, going up, then 0 -> 4 on the top side
+        assert!(trimmed[0].c1.distance(Point::new(0.0, 4.0)) < 0.02, "{trimmed:?}");
+        assert!(trimmed.last().unwrap().c4.distance(Point::new(4.0, 0.0)) < 0.02, "{trimmed:?}");
+    }
+}
@@ -0,0 +1,206 @@
+use super::*;
+use alloc::vec::Vec;
+
+/// Interpolation between two composite bezier curves
+///
+/// Both paths are normalized once, when the morph is created:
+/// - the path with fewer curves gets its longest curves split in half until both have as many
+/// - if both paths are closed, the second one is reversed if it winds the other way,
+///   and rotated so that it starts at the curve start closest to the first path's start
+///
+/// Intermediate paths are then obtained by moving each control point in a straight line.
+/// If both paths are closed, so are intermediate paths.
+#[derive(Debug, Clone)]
+pub struct Morph {
+    start: Vec<CubicBezier>,
+    end: Vec<CubicBezier>,
+}
+
+impl Morph {
+    pub fn new(start: &[CubicBezier], end: &[CubicBezier]) -> Self {
+        if start.is_empty() || end.is_empty() {
+            return Self {
+                start: Vec::new(),
+                end: Vec::new(),
+            };
+        }
+
+        let mut start = start.to_vec();
+        let mut end = end.to_vec();
+
+        if is_closed(&start) && is_closed(&end) {
+            if (analysis::signed_area(&start) < 0.0) != (analysis::signed_area(&end) < 0.0) {
+                analysis::reverse(&mut end);
+            }
+
+            let origin = start[0].c1;
+            let distance = |c: &CubicBezier| c.c1.distance_squared(origin);
+            let closest = (0..end.len()).min_by(|a, b| distance(&end[*a]).total_cmp(&distance(&end[*b])));
+            end.rotate_left(closest.unwrap_or(0));
+        }
+
+        let count = start.len().max(end.len());
+        subdivide_to(&mut start, count);
+        subdivide_to(&mut end, count);
+
+        Self {
+            start,
+            end,
+        }
+    }
+
+    /// The first path, normalized
+    pub fn start(&self) -> &[CubicBezier] {
+        &self.start
+    }
+
+    /// The second path, normalized
+    pub fn end(&self) -> &[CubicBezier] {
+        &self.end
+    }
+
+    /// Computes the intermediate path at `t`, from 0 (start) to 1 (end)
+    ///
+    /// Previous content of `output` is cleared.
+    pub fn at(&self, t: f32, output: &mut Vec<CubicBezier>) {
+        output.clear();
+
+        // connected curves stay connected: equal inputs give equal outputs
+        let lerp = |a: Point, b: Point| a + (b - a) * t;
+
+        for (a, b) in self.start.iter().zip(self.end.iter()) {
+            output.push(CubicBezier {
+                c1: lerp(a.c1, b.c1),
+                c2: lerp(a.c2, b.c2),
+                c3: lerp(a.c3, b.c3),
+                c4: lerp(a.c4, b.c4),
+            });
+        }
+    }
+}
+
+/// Interpolates between two composite bezier curves
+///
+/// This is a shortcut for [`Morph::new`] followed by [`Morph::at`].
+pub fn interpolate(start: &[CubicBezier], end: &[CubicBezier], t: f32) -> Vec<CubicBezier> {
+    let mut output = Vec::new();
+    Morph::new(start, end).at(t, &mut output);
+    output
+}
+
+// splits the longest curves until the path has `count` of them
+fn subdivide_to(path: &mut Vec<CubicBezier>, count: usize) {
+    // length of the control polygon, an upper bound of the curve's length
+    let length = |c: &CubicBezier| c.c1.distance(c.c2) + c.c2.distance(c.c3) + c.c3.distance(c.c4);
+
+    while path.len() < count {
+        let longest = (0..path.len()).max_by(|a, b| length(&path[*a]).total_cmp(&length(&path[*b])));
+        let i = longest.unwrap_or(0);
+
+        let (first_half, second_half) = path[i].split_at(0.5);
+        path[i] = first_half;
+        path.insert(i + 1, second_half);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(points: &[(f32, f32)]) -> Vec<CubicBezier> {
+        let mut builder = PathBuilder::new();
+        builder.move_to(Point::new(points[0].0, points[0].1));
+
+        for (x, y) in &points[1..] {
+            builder.line_to(Point::new(*x, *y));
+        }
+
+        builder.build().remove(0)
+    }
+
+    fn assert_continuous(path: &[CubicBezier], closed: bool) {
+        for pair in path.windows(2) {
+            assert_eq!(pair[0].c4, pair[1].c1);
+        }
+
+        assert_eq!(is_closed(path), closed);
+    }
+
+    #[test]
+    fn different_curve_counts() {
+        let triangle = polygon(&[(50.0, 10.0), (90.0, 90.0), (10.0, 90.0)]);
+        let square = polygon(&[(10.0, 10.0), (90.0, 10.0), (90.0, 90.0), (10.0, 90.0)]);
+        let morph = Morph::new(&triangle, &square);
+
+        assert_eq!(morph.start().len(), 4);
+        assert_eq!(morph.end().len(), 4);
+
+        let mut output = Vec::new();
+
+        for t in [0.0, 0.25, 0.5, 0.75, 1.0] {
+            morph.at(t, &mut output);
+            assert_eq!(output.len(), 4);
+            assert_continuous(&output, true);
+        }
+
+        let points = |path: &[CubicBezier]| -> Vec<[Point; 4]> {
+            path.iter().map(|c| [c.c1, c.c2, c.c3, c.c4]).collect()
+        };
+
+        morph.at(0.0, &mut output);
+        assert_eq!(points(&output), points(morph.start()));
+
+        morph.at(1.0, &mut output);
+        assert_eq!(points(&output), points(morph.end()));
+    }
+
+    #[test]
+    fn opposite_orientation() {
+        let clockwise = polygon(&[(10.0, 10.0), (90.0, 10.0), (90.0, 90.0), (10.0, 90.0)]);
+        let counter_clockwise = polygon(&[(20.0, 20.0), (20.0, 80.0), (80.0, 80.0), (80.0, 20.0)]);
+
+        let morph = Morph::new(&clockwise, &counter_clockwise);
+        assert!(analysis::signed_area(morph.end()) > 0.0);
+
+        // no intermediate path collapses
+        let mut output = Vec::new();
+
+        for t in [0.25, 0.5, 0.75] {
+            morph.at(t, &mut output);
+            assert_continuous(&output, true);
+            assert!(analysis::signed_area(&output) > 3000.0, "{t}");
+        }
+    }
+
+    #[test]
+    fn start_point_alignment() {
+        let square = polygon(&[(10.0, 10.0), (90.0, 10.0), (90.0, 90.0), (10.0, 90.0)]);
+        let rotated = polygon(&[(85.0, 85.0), (15.0, 85.0), (15.0, 15.0), (85.0, 15.0)]);
+
+        let morph = Morph::new(&square, &rotated);
+        assert_eq!(morph.end()[0].c1, Point::new(15.0, 15.0));
+        assert_continuous(morph.end(), true);
+    }
+
+    #[test]
+    fn open_paths() {
+        let mut builder = PathBuilder::new();
+        builder.move_to(Point::new(0.0, 0.0));
+        builder.line_to(Point::new(10.0, 0.0));
+        builder.line_to(Point::new(10.0, 10.0));
+        let start = builder.build_open().remove(0);
+
+        let mut builder = PathBuilder::new();
+        builder.move_to(Point::new(0.0, 20.0));
+        builder.quad_to(Point::new(20.0, 20.0), Point::new(20.0, 40.0));
+        let end = builder.build_open().remove(0);
+
+        let output = interpolate(&start, &end, 0.5);
+        assert_eq!(output.len(), 2);
+        assert_continuous(&output, false);
+        assert_eq!(output[0].c1, Point::new(0.0, 10.0));
+        assert_eq!(output[1].c4, Point::new(15.0, 25.0));
+
+        assert!(interpolate(&start, &[], 0.5).is_empty());
+    }
+}
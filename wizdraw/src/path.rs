@@ -0,0 +1,207 @@
+use super::*;
+use alloc::vec::Vec;
+
+/// Builds composite bezier curves from drawing commands
+///
+/// Lines and quadratic curves are promoted to [`CubicBezier`]s. Every curve
+/// starts at the exact end point of the previous one, so each subpath is a
+/// valid [Composite Bezier Curve](https://en.wikipedia.org/wiki/Composite_B%C3%A9zier_curve)
+/// for [`Canvas::fill_cbc`] and `contour`.
+///
+/// Commands prefixed with `rel_` take coordinates relative to the current point.
+/// Drawing commands issued before any `move_to` start at `(0, 0)`.
+///
+/// ```rust
+/// use wizdraw::{PathBuilder, Point};
+///
+/// let mut builder = PathBuilder::new();
+/// builder.move_to(Point::new(10.0, 10.0));
+/// builder.line_to(Point::new(90.0, 10.0));
+/// builder.quad_to(Point::new(90.0, 90.0), Point::new(10.0, 90.0));
+/// builder.close();
+///
+/// let subpaths = builder.build();
+/// assert_eq!(subpaths.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PathBuilder {
+    subpaths: Vec<Vec<CubicBezier>>,
+    current: Vec<CubicBezier>,
+    start: Point,
+    cursor: Point,
+}
+
+impl PathBuilder {
+    /// Empty builder, starting at `(0, 0)`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The point where the next curve will start
+    pub fn current_point(&self) -> Point {
+        self.cursor
+    }
+
+    /// Starts a new subpath at `p`
+    ///
+    /// The current subpath is kept as is (it isn't closed).
+    pub fn move_to(&mut self, p: Point) -> &mut Self {
+        self.end_subpath(false);
+        self.start = p;
+        self.cursor = p;
+        self
+    }
+
+    /// Starts a new subpath at the current point plus `d`
+    pub fn rel_move_to(&mut self, d: Vec2<f32>) -> &mut Self {
+        self.move_to(self.cursor + d)
+    }
+
+    /// Straight line to `p`
+    pub fn line_to(&mut self, p: Point) -> &mut Self {
+        self.push(CubicBezier::segment(self.cursor, p))
+    }
+
+    /// Straight line by `d`
+    pub fn rel_line_to(&mut self, d: Vec2<f32>) -> &mut Self {
+        self.line_to(self.cursor + d)
+    }
+
+    /// Quadratic curve, promoted to a cubic one
+    pub fn quad_to(&mut self, ctrl: Point, p: Point) -> &mut Self {
+        let from = self.cursor;
+        self.push(CubicBezier {
+            c1: from,
+            c2: from + (ctrl - from) * (2.0 / 3.0),
+            c3: p + (ctrl - p) * (2.0 / 3.0),
+            c4: p,
+        })
+    }
+
+    /// Quadratic curve, with `ctrl` and `d` relative to the current point
+    pub fn rel_quad_to(&mut self, ctrl: Vec2<f32>, d: Vec2<f32>) -> &mut Self {
+        let from = self.cursor;
+        self.quad_to(from + ctrl, from + d)
+    }
+
+    /// Cubic curve to `p`, with control points `ctrl_1` and `ctrl_2`
+    pub fn cubic_to(&mut self, ctrl_1: Point, ctrl_2: Point, p: Point) -> &mut Self {
+        self.push(CubicBezier {
+            c1: self.cursor,
+            c2: ctrl_1,
+            c3: ctrl_2,
+            c4: p,
+        })
+    }
+
+    /// Cubic curve, with all points relative to the current point
+    pub fn rel_cubic_to(&mut self, ctrl_1: Vec2<f32>, ctrl_2: Vec2<f32>, d: Vec2<f32>) -> &mut Self {
+        let from = self.cursor;
+        self.cubic_to(from + ctrl_1, from + ctrl_2, from + d)
+    }
+
+    /// Closes the current subpath with a line to its start, if necessary
+    ///
+    /// The next curve will start a new subpath at the same point.
+    pub fn close(&mut self) -> &mut Self {
+        self.end_subpath(true);
+        self.cursor = self.start;
+        self
+    }
+
+    /// Returns all subpaths, closing the ones which weren't
+    ///
+    /// Each subpath can be passed to [`Canvas::fill_cbc`], or all of
+    /// them at once to [`Canvas::fill_compound`].
+    pub fn build(mut self) -> Vec<Vec<CubicBezier>> {
+        self.end_subpath(false);
+
+        // including the ones ended by `move_to`
+        for subpath in &mut self.subpaths {
+            close_subpath(subpath);
+        }
+
+        self.subpaths
+    }
+
+    /// Returns all subpaths, leaving the ones which weren't closed open
+    ///
+    /// This is useful to stroke open paths with `contour`.
+    pub fn build_open(mut self) -> Vec<Vec<CubicBezier>> {
+        self.end_subpath(false);
+        self.subpaths
+    }
+
+    fn push(&mut self, curve: CubicBezier) -> &mut Self {
+        self.cursor = curve.c4;
+        self.current.push(curve);
+        self
+    }
+
+    fn end_subpath(&mut self, close: bool) {
+        if self.current.is_empty() {
+            return;
+        }
+
+        let mut subpath = core::mem::take(&mut self.current);
+
+        if close {
+            close_subpath(&mut subpath);
+        }
+
+        self.subpaths.push(subpath);
+    }
+}
+
+// adds a line back to the start of a subpath, if it doesn't end there
+fn close_subpath(subpath: &mut Vec<CubicBezier>) {
+    let (Some(first), Some(last)) = (subpath.first(), subpath.last()) else {
+        return;
+    };
+
+    if first.c1 != last.c4 {
+        subpath.push(CubicBezier::segment(last.c4, first.c1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_closes_every_subpath() {
+        let mut builder = PathBuilder::new();
+        builder.move_to(Point::new(10.0, 10.0));
+        builder.line_to(Point::new(50.0, 10.0));
+        builder.line_to(Point::new(50.0, 50.0));
+        builder.move_to(Point::new(60.0, 60.0));
+        builder.line_to(Point::new(90.0, 60.0));
+        builder.line_to(Point::new(90.0, 90.0));
+
+        let subpaths = builder.build();
+        assert_eq!(subpaths.len(), 2);
+
+        for subpath in &subpaths {
+            assert_eq!(subpath.first().unwrap().c1, subpath.last().unwrap().c4);
+        }
+
+        // used to trip the continuity assertion
+        let compound: Vec<&[CubicBezier]> = subpaths.iter().map(Vec::as_slice).collect();
+        let texture = Texture::SolidColor(Color::new(255, 255, 255, 255));
+        let mut canvas = cpu::Canvas::new(100, 100);
+        canvas.fill_compound(&compound, &texture, FillRule::NonZero, SsaaConfig::None);
+    }
+
+    #[test]
+    fn build_open_keeps_subpaths_open() {
+        let mut builder = PathBuilder::new();
+        builder.move_to(Point::new(10.0, 10.0));
+        builder.line_to(Point::new(50.0, 10.0));
+        builder.move_to(Point::new(60.0, 60.0));
+        builder.line_to(Point::new(90.0, 60.0));
+
+        for subpath in builder.build_open() {
+            assert_eq!(subpath.len(), 1);
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use super::*;
+use alloc::vec::Vec;
+
+// where the projected curve is compared to its approximation
+const SAMPLES: [f32; 3] = [0.25, 0.5, 0.75];
+
+/// Projective transformation (3x3 matrix, also known as homography)
+///
+/// A point `(x, y)` is mapped to `(x' / w, y' / w)`, where
+/// `[x', y', w]` is the product of the matrix (row-major) with `[x, y, 1]`.
+///
+/// Projected geometry must stay in front of the viewer, i.e. `w` must be
+/// positive for every point of the input; nothing is clipped.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Perspective {
+    pub matrix: [[f32; 3]; 3],
+}
+
+impl Default for Perspective {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl From<Transform> for Perspective {
+    fn from(t: Transform) -> Self {
+        Self::new([
+            [t.a, t.c, t.e],
+            [t.b, t.d, t.f],
+            [0.0, 0.0, 1.0],
+        ])
+    }
+}
+
+impl Perspective {
+    /// A transform which leaves points untouched
+    pub const IDENTITY: Self = Self::new([
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0],
+    ]);
+
+    pub const fn new(matrix: [[f32; 3]; 3]) -> Self {
+        Self { matrix }
+    }
+
+    /// Maps the unit square to a quadrilateral
+    ///
+    /// `(0, 0)` goes to `top_left`, `(1, 0)` to `top_right`, `(0, 1)` to
+    /// `btm_left` and `(1, 1)` to `btm_right`, like in [`Texture::QuadBitmap`].
+    pub fn square_to_quad(top_left: Point, btm_left: Point, top_right: Point, btm_right: Point) -> Self {
+        let [p0, p1, p2, p3] = [top_left, top_right, btm_right, btm_left];
+        let d1 = p1 - p2;
+        let d2 = p3 - p2;
+        let d3 = p0 - p1 + p2 - p3;
+
+        let (g, h) = match d3 == Vec2::zero() {
+            // parallelogram: the mapping is affine
+            true => (0.0, 0.0),
+            false => {
+                let det = d1.x * d2.y - d2.x * d1.y;
+                let g = (d3.x * d2.y - d2.x * d3.y) / det;
+                let h = (d1.x * d3.y - d3.x * d1.y) / det;
+                (g, h)
+            },
+        };
+
+        Self::new([
+            [p1.x - p0.x + g * p1.x, p3.x - p0.x + h * p3.x, p0.x],
+            [p1.y - p0.y + g * p1.y, p3.y - p0.y + h * p3.y, p0.y],
+            [g, h, 1.0],
+        ])
+    }
+
+    /// Maps a rectangle to a quadrilateral
+    ///
+    /// See [`Perspective::square_to_quad`] for the order of corners.
+    pub fn rect_to_quad(rect: BoundingBox, top_left: Point, btm_left: Point, top_right: Point, btm_right: Point) -> Self {
+        let to_square = Transform::translation(-rect.min).then(&Transform::scaling(rect.size().map(|f| 1.0 / f)));
+        let to_quad = Self::square_to_quad(top_left, btm_left, top_right, btm_right);
+        Self::from(to_square).then(&to_quad)
+    }
+
+    /// Composition: the resulting transform applies `self`, then `other`
+    pub fn then(&self, other: &Self) -> Self {
+        let (a, b) = (&other.matrix, &self.matrix);
+        let cell = |r: usize, c: usize| a[r][0] * b[0][c] + a[r][1] * b[1][c] + a[r][2] * b[2][c];
+
+        Self::new([
+            [cell(0, 0), cell(0, 1), cell(0, 2)],
+            [cell(1, 0), cell(1, 1), cell(1, 2)],
+            [cell(2, 0), cell(2, 1), cell(2, 2)],
+        ])
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+
+    // (x', y', w)
+    #[inline(always)]
+    fn homogeneous(&self, p: Point) -> (Point, f32) {
+        let m = &self.matrix;
+        let x = m[0][0] * p.x + m[0][1] * p.y + m[0][2];
+        let y = m[1][0] * p.x + m[1][1] * p.y + m[1][2];
+        let w = m[2][0] * p.x + m[2][1] * p.y + m[2][2];
+        (Point::new(x, y), w)
+    }
+
+    #[inline(always)]
+    pub fn apply(&self, p: Point) -> Point {
+        let (p, w) = self.homogeneous(p);
+        p / w
+    }
+
+    /// Projects a composite bezier curve
+    ///
+    /// Projected curves generally aren't bezier curves; they are approximated
+    /// with cubic curves, which are split until they are at most `tolerance`
+    /// away from the exact projection. Connected curves stay connected.
+    ///
+    /// Previous content of `output` is cleared.
+    pub fn apply_path(&self, path: &[CubicBezier], tolerance: f32, output: &mut Vec<CubicBezier>) {
+        output.clear();
+
+        for curve in path {
+            self.project(*curve, tolerance, 0, output);
+        }
+    }
+
+    fn project(&self, curve: CubicBezier, tolerance: f32, depth: usize, output: &mut Vec<CubicBezier>) {
+        let (h1, w1) = self.homogeneous(curve.c1);
+        let (h2, w2) = self.homogeneous(curve.c2);
+        let (h3, w3) = self.homogeneous(curve.c3);
+        let (h4, w4) = self.homogeneous(curve.c4);
+
+        let start = h1 / w1;
+        let end = h4 / w4;
+
+        // derivatives of the projection at both ends, divided by 3
+        let d_start = ((h2 - h1) * w1 - h1 * (w2 - w1)) / (w1 * w1);
+        let d_end = ((h4 - h3) * w4 - h4 * (w4 - w3)) / (w4 * w4);
+
+        let candidate = CubicBezier {
+            c1: start,
+            c2: start + d_start,
+            c3: end - d_end,
+            c4: end,
+        };
+
+        let max_sq = tolerance * tolerance;
+        let close_enough = |t: f32| self.apply(curve.eval(t)).distance_squared(candidate.eval(t)) <= max_sq;
+
+        if depth == MAX_DEPTH || SAMPLES.iter().all(|t| close_enough(*t)) {
+            output.push(candidate);
+        } else {
+            let (first_half, second_half) = curve.split_at(0.5);
+            self.project(first_half, tolerance, depth + 1, output);
+            self.project(second_half, tolerance, depth + 1, output);
+        }
+    }
+}
@@ -0,0 +1,426 @@
+use super::*;
+use alloc::vec::Vec;
+
+// conics are never split into more than 2^MAX_POW2 quadratic curves
+const MAX_POW2: usize = 8;
+
+// larger conic weights, including infinity, are clamped to this
+const MAX_WEIGHT: f32 = 1e6;
+
+/// Maximum distance between conics and the cubic curves approximating them
+/// in the default implementation of [`Canvas::fill_segments`]
+pub const CONIC_TOLERANCE: f32 = 0.05;
+
+/// Line, quadratic, cubic or conic (rational quadratic) curve
+///
+/// Conics can represent circular and elliptical arcs exactly: a quarter
+/// circle is a conic whose control point is the corner of its bounding
+/// square, with a weight of `sqrt(2) / 2`. A weight of 1 gives a regular
+/// quadratic curve.
+///
+/// Weights must be positive: conics with a zero, negative or NaN weight are
+/// treated as a line from `from` to `to`. Very large weights (up to infinity)
+/// are clamped to a million, which is close to the lines through `ctrl`.
+#[derive(Copy, Clone, Debug)]
+pub enum Segment {
+    Line {
+        from: Point,
+        to: Point,
+    },
+    Quad {
+        from: Point,
+        ctrl: Point,
+        to: Point,
+    },
+    Cubic(CubicBezier),
+    Conic {
+        from: Point,
+        ctrl: Point,
+        to: Point,
+        weight: f32,
+    },
+}
+
+impl From<CubicBezier> for Segment {
+    fn from(curve: CubicBezier) -> Self {
+        Self::Cubic(curve)
+    }
+}
+
+impl Segment {
+    pub fn start(&self) -> Point {
+        match self {
+            Self::Line { from, .. } => *from,
+            Self::Quad { from, .. } => *from,
+            Self::Cubic(curve) => curve.c1,
+            Self::Conic { from, .. } => *from,
+        }
+    }
+
+    pub fn end(&self) -> Point {
+        match self {
+            Self::Line { to, .. } => *to,
+            Self::Quad { to, .. } => *to,
+            Self::Cubic(curve) => curve.c4,
+            Self::Conic { to, .. } => *to,
+        }
+    }
+
+    /// Point of the segment at `t`, from 0 (start) to 1 (end)
+    pub fn eval(&self, t: f32) -> Point {
+        match self.checked() {
+            Self::Line { from, to } => travel(from, to, t),
+            Self::Quad { from, ctrl, to } => {
+                travel(travel(from, ctrl, t), travel(ctrl, to, t), t)
+            },
+            Self::Cubic(curve) => curve.eval(t),
+            Self::Conic { from, ctrl, to, weight } => {
+                let mt = 1.0 - t;
+                let a = mt * mt;
+                let b = 2.0 * mt * t * weight;
+                let c = t * t;
+                (from * a + ctrl * b + to * c) / (a + b + c)
+            },
+        }
+    }
+
+    /// Splits the segment in two at `t`
+    ///
+    /// The first segment ends exactly where the second one starts.
+    pub fn split_at(self, t: f32) -> (Self, Self) {
+        match self.checked() {
+            Self::Line { from, to } => {
+                let mid = travel(from, to, t);
+                (Self::Line { from, to: mid }, Self::Line { from: mid, to })
+            },
+            Self::Quad { from, ctrl, to } => {
+                let ctrl_1 = travel(from, ctrl, t);
+                let ctrl_2 = travel(ctrl, to, t);
+                let mid = travel(ctrl_1, ctrl_2, t);
+
+                let first = Self::Quad { from, ctrl: ctrl_1, to: mid };
+                let second = Self::Quad { from: mid, ctrl: ctrl_2, to };
+                (first, second)
+            },
+            Self::Cubic(curve) => {
+                let (first, second) = curve.split_at(t);
+                (Self::Cubic(first), Self::Cubic(second))
+            },
+            Self::Conic { from, ctrl, to, weight } => {
+                // De Casteljau's algorithm in homogeneous coordinates
+                let lerp = |a: (Point, f32), b: (Point, f32)| (travel(a.0, b.0, t), a.1 + (b.1 - a.1) * t);
+
+                let p0 = (from, 1.0);
+                let p1 = (ctrl * weight, weight);
+                let p2 = (to, 1.0);
+
+                let a = lerp(p0, p1);
+                let b = lerp(p1, p2);
+                let m = lerp(a, b);
+                let mid = m.0 / m.1;
+
+                let first = Self::Conic {
+                    from,
+                    ctrl: a.0 / a.1,
+                    to: mid,
+                    weight: a.1 / m.1.sqrt(),
+                };
+
+                let second = Self::Conic {
+                    from: mid,
+                    ctrl: b.0 / b.1,
+                    to,
+                    weight: b.1 / m.1.sqrt(),
+                };
+
+                (first, second)
+            },
+        }
+    }
+
+    /// Converts the segment to cubic curves, which are appended to `output`
+    ///
+    /// Lines and quadratic curves are converted exactly. Conics are split
+    /// into quadratic curves which are at most `tolerance` away from them.
+    pub fn to_cubics(&self, tolerance: f32, output: &mut Vec<CubicBezier>) {
+        let checked = self.checked();
+
+        match checked {
+            Self::Line { from, to } => output.push(CubicBezier::segment(from, to)),
+            Self::Quad { from, ctrl, to } => output.push(quad_to_cubic(from, ctrl, to)),
+            Self::Cubic(curve) => output.push(curve),
+            Self::Conic { from, ctrl, to, weight } => {
+                let pow2 = conic_pow2(from, ctrl, to, weight, tolerance);
+                push_conic(checked, pow2, output);
+            },
+        }
+    }
+
+    // replaces conics which have an invalid weight, see the type's doc
+    fn checked(self) -> Self {
+        match self {
+            Self::Conic { from, to, weight, .. } if weight.is_nan() || weight <= 0.0 => Self::Line { from, to },
+            Self::Conic { from, ctrl, to, weight } if weight > MAX_WEIGHT => Self::Conic {
+                from,
+                ctrl,
+                to,
+                weight: MAX_WEIGHT,
+            },
+            other => other,
+        }
+    }
+
+    fn interior(&self) -> [Point; 2] {
+        match self.checked() {
+            Self::Line { from, to } => [from, to],
+            Self::Quad { ctrl, .. } => [ctrl, ctrl],
+            Self::Cubic(curve) => [curve.c2, curve.c3],
+            Self::Conic { ctrl, .. } => [ctrl, ctrl],
+        }
+    }
+}
+
+fn quad_to_cubic(from: Point, ctrl: Point, to: Point) -> CubicBezier {
+    CubicBezier {
+        c1: from,
+        c2: from + (ctrl - from) * (2.0 / 3.0),
+        c3: to + (ctrl - to) * (2.0 / 3.0),
+        c4: to,
+    }
+}
+
+// Number of times a conic must be halved so that each part is close to the
+// quadratic curve with the same control points (error bound from Skia)
+fn conic_pow2(from: Point, ctrl: Point, to: Point, weight: f32, tolerance: f32) -> usize {
+    let a = weight - 1.0;
+    let k = a / (4.0 * (2.0 + a));
+    let mut error = ((from - ctrl * 2.0 + to) * k).magnitude();
+
+    let mut pow2 = 0;
+    while pow2 < MAX_POW2 && error > tolerance {
+        error *= 0.25;
+        pow2 += 1;
+    }
+
+    pow2
+}
+
+fn push_conic(conic: Segment, pow2: usize, output: &mut Vec<CubicBezier>) {
+    match (pow2, conic) {
+        (0, Segment::Conic { from, ctrl, to, .. }) => output.push(quad_to_cubic(from, ctrl, to)),
+        _ => {
+            let (first, second) = conic.split_at(0.5);
+            push_conic(first, pow2 - 1, output);
+            push_conic(second, pow2 - 1, output);
+        },
+    }
+}
+
+// Fallback for backends which only support cubic curves
+pub(crate) fn fill_as_cubics<C: Canvas + ?Sized>(
+    canvas: &mut C,
+    subpaths: &[&[Segment]],
+    texture: &Texture,
+    fill_rule: FillRule,
+    ssaa: SsaaConfig,
+) {
+    let mut curves = Vec::new();
+    let mut ends = Vec::with_capacity(subpaths.len());
+
+    for subpath in subpaths {
+        for segment in *subpath {
+            segment.to_cubics(CONIC_TOLERANCE, &mut curves);
+        }
+
+        ends.push(curves.len());
+    }
+
+    let mut start = 0;
+    let mut cubic_subpaths = Vec::with_capacity(subpaths.len());
+
+    for end in ends {
+        cubic_subpaths.push(&curves[start..end]);
+        start = end;
+    }
+
+    canvas.fill_compound(&cubic_subpaths, texture, fill_rule, ssaa);
+}
+
+/// Common interface of the curves which the CPU rasterizer can process
+pub(crate) trait Curve: Copy {
+    fn start(&self) -> Point;
+    fn end(&self) -> Point;
+    fn split_at(self, t: f32) -> (Self, Self);
+    /// box of the control points
+    fn aabb(&self) -> BoundingBox;
+    /// box of the curve itself, possibly smaller
+    fn bounds(&self) -> BoundingBox;
+    /// control points between the start and the end
+    fn interior(&self) -> [Point; 2];
+
+    fn split_4(self) -> [Self; 4] {
+        let (ab, cd) = self.split_at(0.5);
+        let (a, b) = ab.split_at(0.5);
+        let (c, d) = cd.split_at(0.5);
+        [a, b, c, d]
+    }
+
+    fn overlaps(&self, tile: BoundingBox) -> bool {
+        if self.aabb().overlaps_with(tile) {
+            self.split_4().iter().any(|part| part.aabb().overlaps_with(tile))
+        } else {
+            false
+        }
+    }
+}
+
+impl Curve for CubicBezier {
+    fn start(&self) -> Point {
+        self.c1
+    }
+
+    fn end(&self) -> Point {
+        self.c4
+    }
+
+    fn split_at(self, t: f32) -> (Self, Self) {
+        CubicBezier::split_at(self, t)
+    }
+
+    fn aabb(&self) -> BoundingBox {
+        CubicBezier::aabb(self)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        self.tight_bounds()
+    }
+
+    fn interior(&self) -> [Point; 2] {
+        [self.c2, self.c3]
+    }
+}
+
+impl Curve for Segment {
+    fn start(&self) -> Point {
+        Segment::start(self)
+    }
+
+    fn end(&self) -> Point {
+        Segment::end(self)
+    }
+
+    fn split_at(self, t: f32) -> (Self, Self) {
+        Segment::split_at(self, t)
+    }
+
+    // curves with positive weights are in the hull of their control points
+    fn aabb(&self) -> BoundingBox {
+        let [a, b] = Segment::interior(self);
+        BoundingBox::new(self.start(), self.start())
+            .include(self.end())
+            .include(a)
+            .include(b)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        match self {
+            Self::Cubic(curve) => curve.tight_bounds(),
+            _ => Curve::aabb(self),
+        }
+    }
+
+    fn interior(&self) -> [Point; 2] {
+        Segment::interior(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu;
+
+    const SIZE: usize = 64;
+
+    fn p(x: f32, y: f32) -> Point {
+        Point::new(x, y)
+    }
+
+    // alpha of each pixel after filling `subpath` natively or through cubics
+    fn coverage(subpath: &[Segment], as_cubics: bool) -> Vec<u8> {
+        let mut canvas = cpu::Canvas::new(SIZE, SIZE);
+        let texture = Texture::SolidColor(Color::new(255, 255, 255, 255));
+        let subpaths = [subpath];
+
+        match as_cubics {
+            true => fill_as_cubics(&mut canvas, &subpaths, &texture, FillRule::NonZero, SsaaConfig::X16),
+            false => canvas.fill_segments(&subpaths, &texture, FillRule::NonZero, SsaaConfig::X16),
+        }
+
+        canvas.pixels().iter().map(|pixel| pixel.a).collect()
+    }
+
+    fn assert_same_coverage(subpath: &[Segment]) {
+        let native = coverage(subpath, false);
+        let cubics = coverage(subpath, true);
+
+        // blending onto the transparent canvas squares the coverage
+        let area = |alphas: &[u8]| alphas.iter().map(|a| (*a as f32 / 255.0).sqrt()).sum::<f32>();
+        let max_diff = native.iter().zip(&cubics).map(|(a, b)| a.abs_diff(*b)).max().unwrap();
+
+        assert!(area(&native) > 100.0);
+        assert!((area(&native) - area(&cubics)).abs() < area(&native) * 0.03);
+        // both are flattened, so edge pixels differ a bit, but no more
+        assert!(max_diff < 192, "{}", max_diff);
+    }
+
+    // closed by a line from the end of `curve` back to its start
+    fn closed(curve: Segment) -> [Segment; 2] {
+        [curve, Segment::Line { from: curve.end(), to: curve.start() }]
+    }
+
+    #[test]
+    fn quad_coverage() {
+        let quad = Segment::Quad { from: p(4.0, 60.0), ctrl: p(32.0, -20.0), to: p(60.0, 60.0) };
+        assert_same_coverage(&closed(quad));
+    }
+
+    #[test]
+    fn conic_coverage() {
+        let weights = [0.25, core::f32::consts::FRAC_1_SQRT_2, 1.0, 4.0];
+
+        for weight in weights {
+            let conic = Segment::Conic { from: p(4.0, 60.0), ctrl: p(4.0, 4.0), to: p(60.0, 4.0), weight };
+            assert_same_coverage(&closed(conic));
+        }
+    }
+
+    #[test]
+    fn invalid_weights() {
+        let (from, ctrl, to) = (p(0.0, 0.0), p(10.0, 10.0), p(20.0, 0.0));
+        let line = Segment::Line { from, to };
+
+        for weight in [0.0, -1.0, f32::NAN, f32::NEG_INFINITY] {
+            let conic = Segment::Conic { from, ctrl, to, weight };
+            assert_eq!(conic.eval(0.5), line.eval(0.5));
+
+            let mut cubics = Vec::new();
+            conic.to_cubics(CONIC_TOLERANCE, &mut cubics);
+            assert_eq!(cubics.len(), 1);
+            assert_eq!([cubics[0].c1, cubics[0].c4], [from, to]);
+            assert_eq!(cubics[0].c2.y, 0.0);
+        }
+
+        // close to the lines through `ctrl`
+        let conic = Segment::Conic { from, ctrl, to, weight: f32::INFINITY };
+        assert!(conic.eval(0.5).distance(ctrl) < 0.001);
+
+        let mut cubics = Vec::new();
+        conic.to_cubics(CONIC_TOLERANCE, &mut cubics);
+        assert!(cubics.len() <= 1 << MAX_POW2);
+
+        for curve in cubics {
+            let points = [curve.c1, curve.c2, curve.c3, curve.c4];
+            assert!(points.iter().all(|point| point.x.is_finite() && point.y.is_finite()));
+        }
+    }
+}
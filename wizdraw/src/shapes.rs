@@ -1,4 +1,6 @@
 use super::*;
+use alloc::vec::Vec;
+use core::f32::consts::{FRAC_PI_2, TAU};
 
 pub fn quad(
     top_left: Point,
@@ -42,4 +44,244 @@ pub fn rectangle(origin: Point, size: Vec2<f32>) -> [CubicBezier; 4] {
     quad(top_left, top_right, bottom_left, bottom_right)
 }
 
+/// Approximates an elliptical arc with cubic bezier curves
+///
+/// The ellipse is centered on `center`; its axes have a length of `radii.x`
+/// and `radii.y` before being rotated by `x_rotation` (in radians). The arc
+/// starts at `start_angle` and spans `sweep_angle`, which can be negative.
+/// Angles are measured on the unit circle, before scaling to the radii.
+///
+/// Curves span at most 90 degrees and are split further until their distance
+/// to the real arc is at most `max_error`.
+///
+/// Curves are appended to `output`.
+pub fn arc(
+    center: Point,
+    radii: Vec2<f32>,
+    x_rotation: f32,
+    start_angle: f32,
+    sweep_angle: f32,
+    max_error: f32,
+    output: &mut Vec<CubicBezier>,
+) {
+    let radii = radii.map(|r| r.abs());
+    let radius = radii.x.max(radii.y);
 
+    if sweep_angle == 0.0 || radius == 0.0 {
+        return;
+    }
+
+    let sweep_angle = sweep_angle.clamp(-TAU, TAU);
+    let mut segments = (sweep_angle.abs() / FRAC_PI_2).ceil().max(1.0);
+
+    // https://spencermortensen.com/articles/bezier-circle/
+    // error of the 4/3 * tan(a/4) approximation, on a circle
+    let arc_error = |angle: f32| {
+        let (sin, cos) = (angle * 0.25).sin_cos();
+        radius * (4.0 / 27.0) * sin.powi(6) / (cos * cos)
+    };
+
+    while arc_error(sweep_angle / segments) > max_error && segments < 64.0 {
+        segments += 1.0;
+    }
+
+    let (sin_rot, cos_rot) = x_rotation.sin_cos();
+    let rotate = |v: Vec2<f32>| Vec2::new(
+        v.x * cos_rot - v.y * sin_rot,
+        v.x * sin_rot + v.y * cos_rot,
+    );
+
+    let point = |angle: f32| {
+        let (sin, cos) = angle.sin_cos();
+        center + rotate(Vec2::new(radii.x * cos, radii.y * sin))
+    };
+
+    let derivative = |angle: f32| {
+        let (sin, cos) = angle.sin_cos();
+        rotate(Vec2::new(-radii.x * sin, radii.y * cos))
+    };
+
+    let step = sweep_angle / segments;
+    let k = (4.0 / 3.0) * (step * 0.25).tan();
+
+    let mut angle = start_angle;
+    let mut start = point(angle);
+
+    for _ in 0..(segments as usize) {
+        let next_angle = angle + step;
+        let end = point(next_angle);
+
+        output.push(CubicBezier {
+            c1: start,
+            c2: start + derivative(angle) * k,
+            c3: end - derivative(next_angle) * k,
+            c4: end,
+        });
+
+        angle = next_angle;
+        start = end;
+    }
+}
+
+/// Approximates an elliptical arc given in SVG's endpoint parameterization
+///
+/// The arc goes from `from` to `to`, on an ellipse with `radii` rotated by
+/// `x_rotation` (in radians). `large_arc` and `sweep` select one of the four
+/// possible arcs, as in the SVG `A` path command. Radii which are too small
+/// are scaled up and zero radii result in a straight line.
+///
+/// The first curve starts exactly at `from` and the last one ends exactly at `to`.
+/// See [`arc`] for `max_error`. Curves are appended to `output`.
+#[allow(clippy::too_many_arguments)]
+pub fn svg_arc(
+    from: Point,
+    radii: Vec2<f32>,
+    x_rotation: f32,
+    large_arc: bool,
+    sweep: bool,
+    to: Point,
+    max_error: f32,
+    output: &mut Vec<CubicBezier>,
+) {
+    // https://www.w3.org/TR/SVG2/implnote.html#ArcConversionEndpointToCenter
+
+    if from == to {
+        return;
+    }
+
+    let mut radii = radii.map(|r| r.abs());
+
+    if radii.x == 0.0 || radii.y == 0.0 {
+        output.push(CubicBezier::segment(from, to));
+        return;
+    }
+
+    let (sin_rot, cos_rot) = x_rotation.sin_cos();
+
+    // step 1: compute (x1', y1')
+    let half_diff = (from - to) * 0.5;
+    let p = Vec2::new(
+        cos_rot * half_diff.x + sin_rot * half_diff.y,
+        cos_rot * half_diff.y - sin_rot * half_diff.x,
+    );
+
+    // ensure radii are large enough
+    let lambda = (p.x * p.x) / (radii.x * radii.x) + (p.y * p.y) / (radii.y * radii.y);
+    if lambda > 1.0 {
+        radii *= lambda.sqrt();
+    }
+
+    // step 2: compute (cx', cy')
+    let (rx2, ry2) = (radii.x * radii.x, radii.y * radii.y);
+    let num = rx2 * ry2 - rx2 * p.y * p.y - ry2 * p.x * p.x;
+    let den = rx2 * p.y * p.y + ry2 * p.x * p.x;
+    let sign = match large_arc != sweep {
+        true => 1.0,
+        false => -1.0,
+    };
+
+    let coef = sign * (num / den).max(0.0).sqrt();
+    let center_p = Vec2::new(
+        coef * radii.x * p.y / radii.y,
+        -coef * radii.y * p.x / radii.x,
+    );
+
+    // step 3: compute (cx, cy) from (cx', cy')
+    let mid = (from + to) * 0.5;
+    let center = mid + Vec2::new(
+        cos_rot * center_p.x - sin_rot * center_p.y,
+        sin_rot * center_p.x + cos_rot * center_p.y,
+    );
+
+    // step 4: compute start angle and sweep angle
+    let angle = |u: Vec2<f32>, v: Vec2<f32>| {
+        let cross = u.x * v.y - u.y * v.x;
+        let dot = u.x * v.x + u.y * v.y;
+        cross.atan2(dot)
+    };
+
+    let u = (p - center_p) / radii;
+    let v = (-p - center_p) / radii;
+
+    let start_angle = angle(Vec2::new(1.0, 0.0), u);
+    let mut sweep_angle = angle(u, v);
+
+    if !sweep && sweep_angle > 0.0 {
+        sweep_angle -= TAU;
+    } else if sweep && sweep_angle < 0.0 {
+        sweep_angle += TAU;
+    }
+
+    let first = output.len();
+    arc(center, radii, x_rotation, start_angle, sweep_angle, max_error, output);
+
+    // make sure the arc connects with its surroundings
+    if let Some(curves) = output.get_mut(first..) {
+        if let Some(curve) = curves.first_mut() {
+            curve.c1 = from;
+        }
+
+        if let Some(curve) = curves.last_mut() {
+            curve.c4 = to;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // distance between `center` and points along `curves`
+    fn distances(curves: &[CubicBezier], center: Point) -> impl Iterator<Item = f32> + '_ {
+        let ts = (0..=8).map(|i| i as f32 / 8.0);
+        curves.iter().flat_map(move |curve| ts.clone().map(move |t| curve.eval(t).distance(center)))
+    }
+
+    #[test]
+    fn quarter_circle() {
+        for max_error in [1.0, 0.1, 0.01] {
+            let mut output = Vec::new();
+            arc(Point::zero(), Vec2::broadcast(100.0), 0.0, 0.0, FRAC_PI_2, max_error, &mut output);
+
+            assert!(output[0].c1.distance(Point::new(100.0, 0.0)) < 0.001);
+            assert!(output.last().unwrap().c4.distance(Point::new(0.0, 100.0)) < 0.001);
+
+            let mid = output[0].eval(0.5).distance(Point::zero());
+            assert!((mid - 100.0).abs() <= max_error, "{}", mid);
+
+            for distance in distances(&output, Point::zero()) {
+                assert!((distance - 100.0).abs() <= max_error);
+            }
+        }
+    }
+
+    #[test]
+    fn svg_arc_scales_small_radii() {
+        let (from, to) = (Point::new(0.0, 0.0), Point::new(100.0, 0.0));
+        let mut output = Vec::new();
+        svg_arc(from, Vec2::broadcast(10.0), 0.0, false, true, to, 0.01, &mut output);
+
+        assert_eq!(output[0].c1, from);
+        assert_eq!(output.last().unwrap().c4, to);
+
+        // half circle through the endpoints
+        for distance in distances(&output, Point::new(50.0, 0.0)) {
+            assert!((distance - 50.0).abs() <= 0.01);
+        }
+    }
+
+    #[test]
+    fn svg_arc_degenerate() {
+        let (from, to) = (Point::new(0.0, 0.0), Point::new(100.0, 0.0));
+
+        let mut output = Vec::new();
+        svg_arc(from, Vec2::new(0.0, 10.0), 0.0, false, true, to, 0.01, &mut output);
+        assert_eq!(output.len(), 1);
+        assert_eq!([output[0].c1, output[0].c4], [from, to]);
+        assert_eq!([output[0].c2.y, output[0].c3.y], [0.0, 0.0]);
+
+        output.clear();
+        svg_arc(from, Vec2::broadcast(10.0), 0.0, true, true, from, 0.01, &mut output);
+        assert!(output.is_empty());
+    }
+}
@@ -0,0 +1,503 @@
+//! SVG path data (the `d` attribute of `<path>` elements)
+
+use super::*;
+use alloc::{vec::Vec, string::String};
+use core::fmt::{self, Write};
+
+// maximum distance between arcs and the curves approximating them
+const ARC_TOLERANCE: f32 = 0.01;
+
+/// Why path data couldn't be parsed
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// Path data must start with a `M` or `m` command
+    MissingMoveTo,
+    /// This character cannot appear here
+    UnexpectedCharacter(char),
+    /// The path data ends in the middle of a command
+    UnexpectedEnd,
+    /// A number was expected here
+    ExpectedNumber,
+    /// An arc flag (`0` or `1`) was expected here
+    ExpectedFlag,
+    /// This number is malformed or too large
+    InvalidNumber,
+}
+
+/// Error returned by [`parse_path`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Byte offset of the problem in the path data
+    pub position: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingMoveTo => write!(f, "path data must start with a move-to command"),
+            Self::UnexpectedCharacter(c) => write!(f, "unexpected character {:?}", c),
+            Self::UnexpectedEnd => write!(f, "unexpected end of path data"),
+            Self::ExpectedNumber => write!(f, "expected a number"),
+            Self::ExpectedFlag => write!(f, "expected an arc flag (0 or 1)"),
+            Self::InvalidNumber => write!(f, "invalid number"),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.kind, self.position)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+/// Parses SVG path data, appending its subpaths to `builder`
+///
+/// The whole SVG grammar is supported: all commands (`M`, `L`, `H`, `V`, `C`, `S`,
+/// `Q`, `T`, `A`, `Z`), in their absolute and relative forms, with implicit
+/// repetitions and compact number syntax (e.g. `M1.5.5-3e1`). Arcs are
+/// converted to cubic curves with [`shapes::svg_arc`].
+///
+/// Subpaths which aren't closed with `Z` are left open; use
+/// [`PathBuilder::build`] to close them before filling, or
+/// [`PathBuilder::build_open`] to stroke them as they are.
+///
+/// ```rust
+/// use wizdraw::{PathBuilder, svg::parse_path};
+///
+/// let mut builder = PathBuilder::new();
+/// parse_path("M10 10 h80 v80 h-80 Z M30,30 30,70 70,70z", &mut builder).unwrap();
+/// assert_eq!(builder.build().len(), 2);
+///
+/// let error = parse_path("M10 10 L20", &mut PathBuilder::new()).unwrap_err();
+/// assert_eq!(error.position, 10);
+/// ```
+pub fn parse_path(data: &str, builder: &mut PathBuilder) -> Result<(), ParseError> {
+    let mut parser = Parser {
+        bytes: data.as_bytes(),
+        pos: 0,
+        comma: None,
+    };
+
+    let mut command = None;
+    // control points of the previous command, for `S` and `T`
+    let mut prev_cubic_ctrl = None;
+    let mut prev_quad_ctrl = None;
+
+    parser.skip_wsp();
+
+    while let Some(next) = parser.peek() {
+        let current = if next.is_ascii_alphabetic() && is_command(next) {
+            if command.is_none() && !matches!(next, b'M' | b'm') {
+                return Err(parser.error(ParseErrorKind::MissingMoveTo));
+            }
+
+            parser.pos += 1;
+            parser.comma = None;
+            parser.skip_wsp();
+            next
+        } else if is_number_start(next) {
+            // implicit repetition of the previous command
+            match command {
+                None => return Err(parser.error(ParseErrorKind::MissingMoveTo)),
+                Some(b'Z' | b'z') => return Err(parser.error(ParseErrorKind::UnexpectedCharacter(next as char))),
+                // after a move-to, implicit repetitions are line-to commands
+                Some(b'M') => b'L',
+                Some(b'm') => b'l',
+                Some(previous) => previous,
+            }
+        } else {
+            return Err(parser.error(ParseErrorKind::UnexpectedCharacter(next as char)));
+        };
+
+        let relative = current.is_ascii_lowercase();
+        let from = builder.current_point();
+
+        let offset = match relative {
+            true => from,
+            false => Point::zero(),
+        };
+
+        let mut cubic_ctrl = None;
+        let mut quad_ctrl = None;
+
+        match current.to_ascii_uppercase() {
+            b'M' => {
+                let p = parser.point()? + offset;
+                builder.move_to(p);
+            },
+            b'L' => {
+                let p = parser.point()? + offset;
+                builder.line_to(p);
+            },
+            b'H' => {
+                let x = parser.number()? + offset.x;
+                builder.line_to(Point::new(x, from.y));
+            },
+            b'V' => {
+                let y = parser.number()? + offset.y;
+                builder.line_to(Point::new(from.x, y));
+            },
+            b'C' => {
+                let ctrl_1 = parser.point()? + offset;
+                let ctrl_2 = parser.point()? + offset;
+                let p = parser.point()? + offset;
+                builder.cubic_to(ctrl_1, ctrl_2, p);
+                cubic_ctrl = Some(ctrl_2);
+            },
+            b'S' => {
+                let ctrl_1 = match prev_cubic_ctrl {
+                    Some(prev) => from * 2.0 - prev,
+                    None => from,
+                };
+
+                let ctrl_2 = parser.point()? + offset;
+                let p = parser.point()? + offset;
+                builder.cubic_to(ctrl_1, ctrl_2, p);
+                cubic_ctrl = Some(ctrl_2);
+            },
+            b'Q' => {
+                let ctrl = parser.point()? + offset;
+                let p = parser.point()? + offset;
+                builder.quad_to(ctrl, p);
+                quad_ctrl = Some(ctrl);
+            },
+            b'T' => {
+                let ctrl = match prev_quad_ctrl {
+                    Some(prev) => from * 2.0 - prev,
+                    None => from,
+                };
+
+                let p = parser.point()? + offset;
+                builder.quad_to(ctrl, p);
+                quad_ctrl = Some(ctrl);
+            },
+            b'A' => {
+                let radii = parser.point()?;
+                let x_rotation = parser.number()?;
+                let large_arc = parser.flag()?;
+                let sweep = parser.flag()?;
+                let p = parser.point()? + offset;
+
+                let mut curves = Vec::new();
+                let x_rotation = x_rotation.to_radians();
+                shapes::svg_arc(from, radii, x_rotation, large_arc, sweep, p, ARC_TOLERANCE, &mut curves);
+
+                for curve in curves {
+                    builder.cubic_to(curve.c2, curve.c3, curve.c4);
+                }
+            },
+            // Z
+            _ => {
+                builder.close();
+            },
+        }
+
+        prev_cubic_ctrl = cubic_ctrl;
+        prev_quad_ctrl = quad_ctrl;
+        command = Some(current);
+
+        // a comma must be followed by more arguments
+        if let Some(comma) = parser.comma {
+            if !parser.peek().is_some_and(is_number_start) {
+                return Err(ParseError {
+                    position: comma,
+                    kind: ParseErrorKind::UnexpectedCharacter(','),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// How [`write_path`] formats path data
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct WriteOptions {
+    /// Maximum number of decimals in coordinates
+    pub precision: usize,
+    /// Use relative commands (`m`, `l`, `c`) instead of absolute ones
+    pub relative: bool,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            precision: 3,
+            relative: false,
+        }
+    }
+}
+
+/// Converts subpaths to SVG path data, appending it to `output`
+///
+/// Each subpath starts with a move-to command. Curves whose control points
+/// are on their ends (like the ones of [`shapes::quad`]) are written as
+/// line-to commands and closed subpaths end with `Z`.
+///
+/// With relative commands, rounding errors don't accumulate: each offset
+/// is computed from the rounded position of the previous point.
+///
+/// ```rust
+/// use wizdraw::{shapes, svg::{write_path, WriteOptions}, vek::Vec2, Point};
+///
+/// let square = shapes::rectangle(Point::new(0.0, 0.0), Vec2::new(10.0, 10.0));
+/// let mut data = String::new();
+/// write_path(&[&square], WriteOptions::default(), &mut data).unwrap();
+/// assert_eq!(data, "M0 0 L10 0 10 10 0 10 Z");
+/// ```
+pub fn write_path<W: Write>(subpaths: &[&[CubicBezier]], options: WriteOptions, output: &mut W) -> fmt::Result {
+    let mut writer = Writer {
+        output,
+        options,
+        pen: Point::zero(),
+        prev_command: None,
+    };
+
+    for subpath in subpaths {
+        let (Some(first), Some(last)) = (subpath.first(), subpath.last()) else {
+            continue;
+        };
+
+        writer.command(b'M', &[first.c1])?;
+        let start = writer.pen;
+
+        let closed = is_closed(subpath);
+
+        // `Z` draws the last line
+        let curves = match closed && is_straight(last) {
+            true => &subpath[..subpath.len() - 1],
+            false => subpath,
+        };
+
+        for curve in curves {
+            match is_straight(curve) {
+                true => writer.command(b'L', &[curve.c4])?,
+                false => writer.command(b'C', &[curve.c2, curve.c3, curve.c4])?,
+            }
+        }
+
+        if closed {
+            writer.command(b'Z', &[])?;
+            writer.pen = start;
+        }
+    }
+
+    Ok(())
+}
+
+/// Converts subpaths to SVG path data
+///
+/// See [`write_path`].
+pub fn to_path_data(subpaths: &[&[CubicBezier]], options: WriteOptions) -> String {
+    let mut data = String::new();
+    // writing to a String cannot fail
+    let _ = write_path(subpaths, options, &mut data);
+    data
+}
+
+fn is_straight(curve: &CubicBezier) -> bool {
+    curve.c1 == curve.c2 && curve.c3 == curve.c4
+}
+
+struct Writer<'a, W: Write> {
+    output: &'a mut W,
+    options: WriteOptions,
+    // current point, as a reader of the output would see it
+    pen: Point,
+    prev_command: Option<u8>,
+}
+
+impl<'a, W: Write> Writer<'a, W> {
+    fn command(&mut self, command: u8, points: &[Point]) -> fmt::Result {
+        let command = match self.options.relative {
+            true => command.to_ascii_lowercase(),
+            false => command,
+        };
+
+        if self.prev_command.is_some() {
+            self.output.write_char(' ')?;
+        }
+
+        // implicit repetitions of a move-to are line-to commands
+        let is_repeated = self.prev_command == Some(command) && !matches!(command, b'M' | b'm' | b'Z' | b'z');
+
+        if !is_repeated {
+            self.output.write_char(command as char)?;
+        }
+
+        let origin = match self.options.relative {
+            true => self.pen,
+            false => Point::zero(),
+        };
+
+        for (i, point) in points.iter().enumerate() {
+            if i > 0 {
+                self.output.write_char(' ')?;
+            }
+
+            let x = self.number(point.x - origin.x)?;
+            self.output.write_char(' ')?;
+            let y = self.number(point.y - origin.y)?;
+
+            if i == points.len() - 1 {
+                self.pen = origin + Vec2::new(x, y);
+            }
+        }
+
+        self.prev_command = Some(command);
+        Ok(())
+    }
+
+    // writes a rounded number, returning the value a reader would parse
+    fn number(&mut self, value: f32) -> Result<f32, fmt::Error> {
+        let mut text = String::new();
+        write!(text, "{:.*}", self.options.precision, value)?;
+
+        if text.contains('.') {
+            let trimmed = text.trim_end_matches('0').trim_end_matches('.').len();
+            text.truncate(trimmed);
+        }
+
+        if text == "-0" {
+            text.remove(0);
+        }
+
+        self.output.write_str(&text)?;
+        Ok(text.parse().unwrap_or(value))
+    }
+}
+
+fn is_command(c: u8) -> bool {
+    b"MLHVCSQTAZmlhvcsqtaz".contains(&c)
+}
+
+fn is_number_start(c: u8) -> bool {
+    c.is_ascii_digit() || matches!(c, b'+' | b'-' | b'.')
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    // position of the comma skipped after the last argument, if any
+    comma: Option<usize>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            position: self.pos,
+            kind,
+        }
+    }
+
+    fn skip_wsp(&mut self) {
+        while let Some(b' ' | b'\t' | b'\r' | b'\n' | b'\x0C') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    // separator between two arguments
+    fn skip_comma_wsp(&mut self) {
+        self.skip_wsp();
+        self.comma = None;
+
+        if self.peek() == Some(b',') {
+            self.comma = Some(self.pos);
+            self.pos += 1;
+            self.skip_wsp();
+        }
+    }
+
+    fn expect_argument(&self) -> Result<(), ParseError> {
+        match self.peek() {
+            None => Err(self.error(ParseErrorKind::UnexpectedEnd)),
+            Some(_) => Ok(()),
+        }
+    }
+
+    fn number(&mut self) -> Result<f32, ParseError> {
+        self.expect_argument()?;
+
+        let start = self.pos;
+        let digits = |parser: &mut Self| {
+            let first = parser.pos;
+            while parser.peek().is_some_and(|c| c.is_ascii_digit()) {
+                parser.pos += 1;
+            }
+            parser.pos > first
+        };
+
+        if let Some(b'+' | b'-') = self.peek() {
+            self.pos += 1;
+        }
+
+        let mut has_digits = digits(self);
+
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            has_digits |= digits(self);
+        }
+
+        if !has_digits {
+            self.pos = start;
+            return Err(self.error(ParseErrorKind::ExpectedNumber));
+        }
+
+        if let Some(b'e' | b'E') = self.peek() {
+            self.pos += 1;
+
+            if let Some(b'+' | b'-') = self.peek() {
+                self.pos += 1;
+            }
+
+            if !digits(self) {
+                return Err(self.error(ParseErrorKind::InvalidNumber));
+            }
+        }
+
+        // the slice only contains ASCII characters
+        let text = core::str::from_utf8(&self.bytes[start..self.pos]).unwrap();
+
+        let number = match text.parse::<f32>() {
+            Ok(number) if number.is_finite() => number,
+            _ => {
+                return Err(ParseError {
+                    position: start,
+                    kind: ParseErrorKind::InvalidNumber,
+                });
+            },
+        };
+
+        self.skip_comma_wsp();
+        Ok(number)
+    }
+
+    fn point(&mut self) -> Result<Point, ParseError> {
+        let x = self.number()?;
+        let y = self.number()?;
+        Ok(Point::new(x, y))
+    }
+
+    // flags don't need separators: `a1 1 0 1150 50` is valid
+    fn flag(&mut self) -> Result<bool, ParseError> {
+        self.expect_argument()?;
+
+        let flag = match self.peek() {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(self.error(ParseErrorKind::ExpectedFlag)),
+        };
+
+        self.pos += 1;
+        self.skip_comma_wsp();
+        Ok(flag)
+    }
+}
@@ -0,0 +1,134 @@
+use super::*;
+
+/// 2D affine transformation (2x3 matrix)
+///
+/// A point `(x, y)` is mapped to `(a * x + c * y + e, b * x + d * y + f)`,
+/// which is the same convention as SVG's `matrix(a, b, c, d, e, f)`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform {
+    /// A transform which leaves points untouched
+    pub const IDENTITY: Self = Self::new(1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+
+    pub const fn new(a: f32, b: f32, c: f32, d: f32, e: f32, f: f32) -> Self {
+        Self { a, b, c, d, e, f }
+    }
+
+    pub const fn translation(offset: Vec2<f32>) -> Self {
+        Self::new(1.0, 0.0, 0.0, 1.0, offset.x, offset.y)
+    }
+
+    pub const fn scaling(factors: Vec2<f32>) -> Self {
+        Self::new(factors.x, 0.0, 0.0, factors.y, 0.0, 0.0)
+    }
+
+    /// Rotation around the origin, in radians
+    pub fn rotation(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::new(cos, sin, -sin, cos, 0.0, 0.0)
+    }
+
+    /// Rotation around `center`, in radians
+    pub fn rotation_around(angle: f32, center: Point) -> Self {
+        Self::translation(-center)
+            .then(&Self::rotation(angle))
+            .then(&Self::translation(center))
+    }
+
+    /// Composition: the resulting transform applies `self`, then `other`
+    pub fn then(&self, other: &Self) -> Self {
+        Self {
+            a: other.a * self.a + other.c * self.b,
+            b: other.b * self.a + other.d * self.b,
+            c: other.a * self.c + other.c * self.d,
+            d: other.b * self.c + other.d * self.d,
+            e: other.a * self.e + other.c * self.f + other.e,
+            f: other.b * self.e + other.d * self.f + other.f,
+        }
+    }
+
+    /// Returns the inverse transform, if this one isn't degenerate
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.a * self.d - self.b * self.c;
+
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+
+        let inv_det = 1.0 / det;
+        let a = self.d * inv_det;
+        let b = -self.b * inv_det;
+        let c = -self.c * inv_det;
+        let d = self.a * inv_det;
+
+        Some(Self {
+            a,
+            b,
+            c,
+            d,
+            e: -(a * self.e + c * self.f),
+            f: -(b * self.e + d * self.f),
+        })
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+
+    #[inline(always)]
+    pub fn apply(&self, p: Point) -> Point {
+        Point {
+            x: self.a * p.x + self.c * p.y + self.e,
+            y: self.b * p.x + self.d * p.y + self.f,
+        }
+    }
+
+    /// Applies the transform to a vector (translation is ignored)
+    #[inline(always)]
+    pub fn apply_vector(&self, v: Vec2<f32>) -> Vec2<f32> {
+        Vec2 {
+            x: self.a * v.x + self.c * v.y,
+            y: self.b * v.x + self.d * v.y,
+        }
+    }
+
+    pub fn apply_curve(&self, curve: &CubicBezier) -> CubicBezier {
+        CubicBezier {
+            c1: self.apply(curve.c1),
+            c2: self.apply(curve.c2),
+            c3: self.apply(curve.c3),
+            c4: self.apply(curve.c4),
+        }
+    }
+
+    /// Transforms points in place
+    pub fn apply_points(&self, points: &mut [Point]) {
+        for point in points {
+            *point = self.apply(*point);
+        }
+    }
+
+    /// Transforms curves in place
+    ///
+    /// Since every control point is mapped identically, curves which were
+    /// connected stay connected.
+    pub fn apply_path(&self, path: &mut [CubicBezier]) {
+        for curve in path {
+            *curve = self.apply_curve(curve);
+        }
+    }
+}